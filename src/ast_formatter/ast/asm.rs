@@ -0,0 +1,123 @@
+use crate::ast_formatter::AstFormatter;
+use crate::ast_formatter::brackets::Brackets;
+use crate::ast_formatter::list::options::ListOptions;
+use crate::ast_formatter::tail::Tail;
+use crate::error::FormatResult;
+use rustc_ast::ast;
+use rustc_parse::parser::asm::{AsmArg, AsmArgKind};
+
+impl AstFormatter {
+    /// Formats an argument of `asm!`, `global_asm!` or `naked_asm!`
+    pub fn asm_arg(&self, arg: &AsmArg, tail: Tail) -> FormatResult {
+        self.with_attrs_tail(&arg.attributes.0, arg.span.into(), tail, || {
+            match arg.kind {
+                AsmArgKind::Template(ref template) => self.expr_tail(template, tail)?,
+                AsmArgKind::Operand(name, ref operand) => {
+                    if name.is_some() {
+                        self.out.copy_next_token()?;
+                        self.out.space_token_space("=")?;
+                    }
+                    self.asm_operand(operand, tail)?;
+                }
+                AsmArgKind::Options(ref options) => {
+                    self.out.token("options")?;
+                    self.list(
+                        Brackets::Parens,
+                        options,
+                        |af, option, tail, _lcx| {
+                            af.out.token_from_source(option.span.into())?;
+                            af.tail(tail)?;
+                            Ok(())
+                        },
+                        ListOptions { tail, .. },
+                    )?;
+                }
+                AsmArgKind::ClobberAbi(ref abis) => {
+                    self.out.token("clobber_abi")?;
+                    self.list(
+                        Brackets::Parens,
+                        abis,
+                        |af, &(_abi, span), tail, _lcx| {
+                            af.out.token_from_source(span.into())?;
+                            af.tail(tail)?;
+                            Ok(())
+                        },
+                        ListOptions { tail, .. },
+                    )?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn asm_operand(&self, operand: &ast::InlineAsmOperand, tail: Tail) -> FormatResult {
+        match *operand {
+            ast::InlineAsmOperand::In { reg: _, ref expr } => {
+                self.asm_reg("in")?;
+                self.expr_tail(expr, tail)?;
+            }
+            ast::InlineAsmOperand::Out {
+                reg: _,
+                late,
+                ref expr,
+            } => {
+                self.asm_reg(if late { "lateout" } else { "out" })?;
+                self.asm_out_expr(expr.as_deref(), tail)?;
+            }
+            ast::InlineAsmOperand::InOut {
+                reg: _,
+                late,
+                ref expr,
+            } => {
+                self.asm_reg(if late { "inlateout" } else { "inout" })?;
+                self.expr_tail(expr, tail)?;
+            }
+            ast::InlineAsmOperand::SplitInOut {
+                reg: _,
+                late,
+                ref in_expr,
+                ref out_expr,
+            } => {
+                self.asm_reg(if late { "inlateout" } else { "inout" })?;
+                self.expr(in_expr)?;
+                self.out.space_token_space("=>")?;
+                self.asm_out_expr(out_expr.as_deref(), tail)?;
+            }
+            ast::InlineAsmOperand::Const { ref anon_const } => {
+                self.out.token_space("const")?;
+                self.expr_tail(&anon_const.value, tail)?;
+            }
+            ast::InlineAsmOperand::Sym { ref sym } => {
+                self.out.token_space("sym")?;
+                self.qpath(&sym.qself, &sym.path, true, tail)?;
+            }
+            ast::InlineAsmOperand::Label { ref block } => {
+                self.out.token_space("label")?;
+                self.block_expr(false, block)?;
+                self.tail(tail)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats `in(reg) ` or `out("eax") `, for example
+    fn asm_reg(&self, token: &'static str) -> FormatResult {
+        self.out.token(token)?;
+        self.out.token("(")?;
+        // a register class is an identifier and an explicit register is a string literal
+        self.out.copy_next_token()?;
+        self.out.token(")")?;
+        self.out.space()?;
+        Ok(())
+    }
+
+    fn asm_out_expr(&self, expr: Option<&ast::Expr>, tail: Tail) -> FormatResult {
+        match expr {
+            Some(expr) => self.expr_tail(expr, tail),
+            None => {
+                self.out.token("_")?;
+                self.tail(tail)
+            }
+        }
+    }
+}
@@ -5,13 +5,18 @@ use crate::ast_formatter::list::options::{
 };
 use crate::ast_formatter::tail::Tail;
 use crate::ast_formatter::width_thresholds::WIDTH_THRESHOLDS;
-use crate::ast_utils::is_rustfmt_skip;
+use crate::ast_utils::{is_rustfmt_skip, myrustfmt_allow_names, rustfmt_skip_names};
 use crate::constraints::VStruct;
 use crate::error::{FormatErrorKind, FormatResult, VerticalError};
+use crate::error_emitter::ErrorKind;
 use crate::span::Span;
 use crate::util::cell_ext::CellExt;
+use crate::util::drop::{Guard, drop_fn};
 use crate::whitespace::VerticalWhitespaceMode;
+use enumset::EnumSet;
 use rustc_ast::ast;
+use rustc_span::{Symbol, sym};
+use std::cell::Cell;
 
 impl AstFormatter {
     pub fn with_attrs(
@@ -33,30 +38,73 @@ impl AstFormatter {
         tail: Tail,
         format: impl FnOnce() -> FormatResult,
     ) -> FormatResult {
-        self.has_vstruct_if(!attrs.is_empty(), VStruct::NonBlockIndent, || {
-            // todo skip attributes as well?
-            attrs.iter().try_for_each(|attr| self.attr(attr))?;
-            // todo make my own attribute? or comment?
-            // handle #[rustfmt::skip]
-            let did_format = !attrs.iter().any(is_rustfmt_skip) && self.format_or_emit(format)?;
-            if !did_format {
-                let _guard = self.constraints().width_limit.replace_guard(None);
-                self.out.copy_span(span)?;
-                self.tail(tail)?;
-            }
-            Ok(())
+        if self.out.is_format_off(&span) {
+            // copy outer attributes too, except doc comments which are handled as comments
+            let attrs_lo = attrs
+                .iter()
+                .filter(|attr| attr.style == ast::AttrStyle::Outer && !attr.is_doc_comment())
+                .map(|attr| attr.span.lo())
+                .min();
+            let span = Span {
+                lo: attrs_lo.map_or(span.lo, |lo| lo.min(span.lo)),
+                hi: span.hi,
+            };
+            let _guard = self.constraints().width_limit.replace_guard(None);
+            self.out.copy_span(span)?;
+            self.tail(tail)?;
+            return Ok(());
+        }
+        self.with_skip_names(attrs, || {
+            self.has_vstruct_if(!attrs.is_empty(), VStruct::NonBlockIndent, || {
+                // inner attributes are emitted by the format function
+                attrs
+                    .iter()
+                    .filter(|attr| attr.style == ast::AttrStyle::Outer)
+                    .try_for_each(|attr| self.attr(attr))?;
+                // todo make my own attribute? or comment?
+                // handle #[rustfmt::skip]
+                let did_format = !attrs.iter().any(is_rustfmt_skip) && self.format_or_emit(format)?;
+                if !did_format {
+                    let _guard = self.constraints().width_limit.replace_guard(None);
+                    self.out.copy_span(span)?;
+                    self.tail(tail)?;
+                }
+                Ok(())
+            })
         })
     }
 
+    /// Adds the names in `#[rustfmt::skip::attributes(..)]` and `#[rustfmt::skip::macros(..)]` to
+    /// the names that are skipped within the given scope, and the errors in
+    /// `#[myrustfmt::allow(..)]` to the errors that are allowed
+    fn with_skip_names(
+        &self,
+        attrs: &[ast::Attribute],
+        scope: impl FnOnce() -> FormatResult,
+    ) -> FormatResult {
+        if attrs.is_empty() {
+            return scope();
+        }
+        let _attributes_guard = push_skip_names(&self.skip_attributes, attrs, sym::attributes);
+        let _macros_guard = push_skip_names(&self.skip_macros, attrs, Symbol::intern("macros"));
+        let allowed = myrustfmt_allowed_errors(attrs);
+        let _allowed_guard = self.errors.allowed.map_guard(|set| set | allowed);
+        scope()
+    }
+
+    pub fn is_skipped_macro(&self, name: Symbol) -> bool {
+        self.skip_macros.with_taken(|names| names.contains(&name))
+    }
+
     /// This is a "last resort" fallback for when a constraint error occurs, but we have no
     /// formatting strategy to try next. This means we have no way of formatting the user's code
     /// with the given constraints. So the error should be reported to the user, and we'll just copy
     /// the source as-is.
     fn format_or_emit(&self, format: impl FnOnce() -> FormatResult) -> FormatResult<bool> {
         let checkpoint = self.out.checkpoint_without_buffer_errors();
-        let error_count_before = self.errors.error_count();
+        let report_count_before = self.errors.report_count();
         let Err(err) = format() else { return Ok(true) };
-        let (line, col) = self.out.line_col();
+        let (line, col) = (self.out.line(), self.out.char_col());
         match err.kind {
             // todo test all these outputs
             FormatErrorKind::Vertical(vertical)
@@ -79,22 +127,38 @@ impl AstFormatter {
             FormatErrorKind::Logical | FormatErrorKind::WidthLimitExceeded => return Err(err),
         }
         assert!(
-            self.errors.error_count() > error_count_before,
+            self.errors.report_count() > report_count_before,
             "an error should be emitted before copy fallback",
         );
         self.out.restore_checkpoint(&checkpoint);
         Ok(false)
     }
 
-    fn attr(&self, attr: &ast::Attribute) -> FormatResult {
-        match attr.kind {
+    pub fn attr(&self, attr: &ast::Attribute) -> FormatResult {
+        if let Some(mode) = self.attr_without_newline(attr)? {
+            self.out.newline(mode)?;
+            self.out.indent();
+        }
+        Ok(())
+    }
+
+    /// Writes an attribute and returns the whitespace mode of the newline that should follow it,
+    /// or `None` if nothing was written
+    pub fn attr_without_newline(
+        &self,
+        attr: &ast::Attribute,
+    ) -> FormatResult<Option<VerticalWhitespaceMode>> {
+        let is_skipped = attr.name().is_some_and(|name| {
+            self.skip_attributes.with_taken(|names| names.contains(&name))
+        });
+        let mode = match attr.kind {
             // comments are handled by SourceFormatter
-            ast::AttrKind::DocComment(_comment_kind, _symbol) => {}
-            ast::AttrKind::Normal(_) => match attr.meta() {
+            ast::AttrKind::DocComment(_comment_kind, _symbol) => return Ok(None),
+            ast::AttrKind::Normal(_) => match attr.meta().filter(|_| !is_skipped) {
                 None => {
                     // todo do better, format key-value pairs
                     self.out.copy_span(attr.span.into())?;
-                    self.out.newline_indent(VerticalWhitespaceMode::Break)?;
+                    VerticalWhitespaceMode::Break
                 }
                 Some(meta) => {
                     self.out.token("#")?;
@@ -108,14 +172,13 @@ impl AstFormatter {
                     self.meta_item(&meta)?;
                     self.out.token("]")?;
                     match attr.style {
-                        ast::AttrStyle::Inner => self.out.newline(VerticalWhitespaceMode::Between)?,
-                        ast::AttrStyle::Outer => self.out.newline(VerticalWhitespaceMode::Break)?,
+                        ast::AttrStyle::Inner => VerticalWhitespaceMode::Between,
+                        ast::AttrStyle::Outer => VerticalWhitespaceMode::Break,
                     }
-                    self.out.indent();
                 }
             },
-        }
-        Ok(())
+        };
+        Ok(Some(mode))
     }
 
     // todo tail?
@@ -164,3 +227,26 @@ impl AstFormatter {
         self.out.copy_span(lit.span.into())
     }
 }
+
+fn push_skip_names<'a>(
+    names: &'a Cell<Vec<Symbol>>,
+    attrs: &[ast::Attribute],
+    kind: Symbol,
+) -> impl Guard + 'a {
+    let added = attrs.iter().flat_map(|attr| rustfmt_skip_names(attr, kind));
+    let prev_len = names.with_taken(|names| {
+        let prev_len = names.len();
+        names.extend(added);
+        prev_len
+    });
+    drop_fn(move || names.with_taken(|names| names.truncate(prev_len)))
+}
+
+/// Collects the error kinds in `#[myrustfmt::allow(..)]` attributes. Unknown names are ignored.
+fn myrustfmt_allowed_errors(attrs: &[ast::Attribute]) -> EnumSet<ErrorKind> {
+    attrs
+        .iter()
+        .flat_map(myrustfmt_allow_names)
+        .filter_map(|name| ErrorKind::from_name(name.as_str()))
+        .collect()
+}
@@ -6,10 +6,13 @@ use crate::ast_utils::spans::block_inside_span;
 use crate::ast_utils::{is_jump_expr, plain_block};
 use crate::error::FormatResult;
 use crate::span::{Span, get_span};
-use crate::util::whitespace_utils::{is_whitespace, is_whitespace_or_semicolon};
+use crate::util::cell_ext::CellExt;
+use crate::util::whitespace_utils::{
+    first_token_after_whitespace_and_comments, is_whitespace, is_whitespace_or_semicolon,
+};
 use crate::whitespace::VerticalWhitespaceMode;
 use rustc_ast::ast;
-use rustc_span::Pos;
+use rustc_span::{BytePos, Pos};
 
 impl AstFormatter {
     pub fn block_expr(&self, omit_open_bracket: bool, block: &ast::Block) -> FormatResult {
@@ -93,14 +96,42 @@ impl AstFormatter {
         if !omit_open_brace {
             self.out.token("{")?;
         }
+        let inner_attrs = self.inner_attrs_at_block_start();
         match contents {
-            None => self.enclosed_empty_contents()?,
-            Some(contents) => self.enclosed_contents(contents)?,
+            None if inner_attrs.is_empty() => self.enclosed_empty_contents()?,
+            // inner attributes are formatted like those at the crate root
+            None => self.enclosed_contents(|| {
+                let (last, rest) = inner_attrs.split_last().unwrap();
+                rest.iter().try_for_each(|attr| self.attr(attr))?;
+                self.attr_without_newline(last)?;
+                Ok(())
+            })?,
+            Some(contents) => self.enclosed_contents(|| {
+                inner_attrs.iter().try_for_each(|attr| self.attr(attr))?;
+                contents()
+            })?,
         }
         self.out.token("}")?;
         Ok(())
     }
 
+    /// Returns the inner attributes of the enclosing item if they are next in source, which means
+    /// this block is the item's body
+    fn inner_attrs_at_block_start(&self) -> Vec<ast::Attribute> {
+        self.inner_attrs.with_taken(|attrs| {
+            let Some(first) = attrs.first() else {
+                return Vec::new();
+            };
+            let (distance, _) =
+                first_token_after_whitespace_and_comments(self.out.source_reader.remaining());
+            if self.out.source_reader.pos() + BytePos(distance) == first.span.lo() {
+                attrs.clone()
+            } else {
+                Vec::new()
+            }
+        })
+    }
+
     pub fn stmt(&self, stmt: &ast::Stmt) -> FormatResult {
         match &stmt.kind {
             ast::StmtKind::Let(local) => self.local(local),
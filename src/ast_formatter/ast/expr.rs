@@ -47,6 +47,9 @@ impl AstFormatter {
             ast::ExprKind::AssignOp(op, ref left, ref right) => {
                 self.expr_infix(left, op.node.as_str(), right, take_tail())?
             }
+            ast::ExprKind::Become(ref target) => {
+                self.jump_with_value("become", Some(target), take_tail())?
+            }
             ast::ExprKind::Binary(op, ref left, ref right) => {
                 self.binary_expr(left, right, op, take_tail())?
             }
@@ -71,11 +74,14 @@ impl AstFormatter {
                 label,
                 ..
             } => self.for_loop(pat, iter, body, label)?,
+            ast::ExprKind::Gen(ref capture_by, ref block, ref kind, _) => {
+                self.gen_block(capture_by, block, kind, take_tail())?
+            }
             ast::ExprKind::If(ref condition, ref block, ref else_) => {
                 self.if_(condition, block, else_.as_deref(), take_tail())?
             }
             ast::ExprKind::Let(ref pat, ref init, ..) => self.let_(pat, init, take_tail())?,
-            ast::ExprKind::Lit(_) => self.out.copy_span(expr.span.into())?,
+            ast::ExprKind::Lit(_) => self.out.copy_literal(expr.span.into())?,
             ast::ExprKind::Loop(ref block, label, _) => self.loop_(label, block)?,
             ast::ExprKind::MacCall(ref mac_call) => {
                 self.macro_call(mac_call, MacCallSemi::No, take_tail())?
@@ -94,29 +100,37 @@ impl AstFormatter {
             ast::ExprKind::Repeat(ref element, ref count) => {
                 self.repeat(element, count, take_tail())?
             }
-            ast::ExprKind::Ret(ref target) => self.return_(target.as_deref(), take_tail())?,
+            ast::ExprKind::Ret(ref target) => {
+                self.jump_with_value("return", target.as_deref(), take_tail())?
+            }
             ast::ExprKind::Struct(ref struct_) => self.struct_expr(struct_, take_tail())?,
+            ast::ExprKind::TryBlock(ref block) => {
+                self.out.token_space("try")?;
+                self.block_expr_allow_horizontal(None, block, take_tail())?
+            }
             ast::ExprKind::Tup(ref items) => self.tuple(items, take_tail())?,
             ast::ExprKind::Unary(op, ref target) => {
                 self.out.token(op.as_str())?;
                 self.expr_tail(target, take_tail())?;
             }
             ast::ExprKind::Underscore => self.out.token("_")?,
+            ast::ExprKind::UnsafeBinderCast(kind, ref target, ref ty) => {
+                self.unsafe_binder_cast(kind, target, ty.as_deref(), take_tail())?
+            }
             ast::ExprKind::While(ref condition, ref block, _label) => {
                 self.while_(condition, block)?
             }
-            ast::ExprKind::Become(_)
+            ast::ExprKind::Yeet(ref target) => {
+                self.out.token_space("do")?;
+                self.jump_with_value("yeet", target.as_deref(), take_tail())?
+            }
+            ast::ExprKind::Yield(ast::YieldKind::Prefix(ref target)) => {
+                self.jump_with_value("yield", target.as_deref(), take_tail())?
+            }
             // todo
-            | ast::ExprKind::FormatArgs(_)
-            | ast::ExprKind::Gen(..)
+            ast::ExprKind::FormatArgs(_)
             | ast::ExprKind::InlineAsm(_)
-            | ast::ExprKind::Match(.., ast::MatchKind::Postfix)
-            | ast::ExprKind::TryBlock(_)
-            | ast::ExprKind::Type(..)
-            | ast::ExprKind::UnsafeBinderCast(..)
-            | ast::ExprKind::Use(..)
-            | ast::ExprKind::Yeet(_)
-            | ast::ExprKind::Yield(_) => return Err(self.err(FormatErrorKind::UnsupportedSyntax)),
+            | ast::ExprKind::Type(..) => return Err(self.err(FormatErrorKind::UnsupportedSyntax)),
             ast::ExprKind::Dummy
             | ast::ExprKind::Err(_)
             | ast::ExprKind::IncludedBytes(_)
@@ -326,6 +340,30 @@ impl AstFormatter {
         })
     }
 
+    /// Formats an `async`, `gen` or `async gen` block
+    fn gen_block(
+        &self,
+        capture_by: &ast::CaptureBy,
+        block: &ast::Block,
+        kind: &ast::GenBlockKind,
+        tail: Tail,
+    ) -> FormatResult {
+        match *kind {
+            ast::GenBlockKind::Async => self.out.token_space("async")?,
+            ast::GenBlockKind::Gen => self.out.token_space("gen")?,
+            ast::GenBlockKind::AsyncGen => {
+                self.out.token_space("async")?;
+                self.out.token_space("gen")?;
+            }
+        }
+        match *capture_by {
+            ast::CaptureBy::Ref => {}
+            ast::CaptureBy::Use { .. } => return Err(self.err(FormatErrorKind::UnsupportedSyntax)),
+            ast::CaptureBy::Value { .. } => self.out.token_space("move")?,
+        }
+        self.block_expr_allow_horizontal(None, block, tail)
+    }
+
     fn if_<'a>(
         &self,
         condition: &ast::Expr,
@@ -391,6 +429,22 @@ impl AstFormatter {
         })
     }
 
+    /// Formats `return`, `become`, `yield` or `yeet`, with an optional value
+    fn jump_with_value(
+        &self,
+        keyword: &'static str,
+        target: Option<&ast::Expr>,
+        tail: Tail,
+    ) -> FormatResult {
+        self.out.token(keyword)?;
+        let Some(target) = target else {
+            return self.tail(tail);
+        };
+        self.out.space()?;
+        self.expr_tail(target, tail)?;
+        Ok(())
+    }
+
     pub fn label(&self, label: ast::Label) -> FormatResult {
         self.ident(label.ident)
     }
@@ -488,16 +542,6 @@ impl AstFormatter {
         Ok(())
     }
 
-    fn return_(&self, target: Option<&ast::Expr>, tail: Tail) -> FormatResult {
-        self.out.token("return")?;
-        let Some(target) = target else {
-            return self.tail(tail);
-        };
-        self.out.space()?;
-        self.expr_tail(target, tail)?;
-        Ok(())
-    }
-
     fn struct_expr(&self, struct_: &ast::StructExpr, tail: Tail) -> FormatResult {
         let first_line = self.out.line();
         self.qpath(&struct_.qself, &struct_.path, true, None)?;
@@ -569,6 +613,32 @@ impl AstFormatter {
         )
     }
 
+    /// `builtin # wrap_binder(expr)` or `builtin # unwrap_binder(expr, Ty)`
+    fn unsafe_binder_cast(
+        &self,
+        kind: ast::UnsafeBinderCastKind,
+        target: &ast::Expr,
+        ty: Option<&ast::Ty>,
+        tail: Tail,
+    ) -> FormatResult {
+        self.out.token("builtin")?;
+        self.out.space_token_space("#")?;
+        self.out.token(match kind {
+            ast::UnsafeBinderCastKind::Wrap => "wrap_binder",
+            ast::UnsafeBinderCastKind::Unwrap => "unwrap_binder",
+        })?;
+        self.out.token("(")?;
+        self.expr(target)?;
+        if let Some(ty) = ty {
+            self.out.token(",")?;
+            self.out.space()?;
+            self.ty(ty)?;
+        }
+        self.out.token(")")?;
+        self.tail(tail)?;
+        Ok(())
+    }
+
     pub fn while_(&self, condition: &ast::Expr, block: &ast::Block) -> FormatResult {
         self.has_vstruct(VStruct::ControlFlow, || {
             self.control_flow_header("while", condition)?;
@@ -11,11 +11,16 @@ impl AstFormatter {
     pub fn match_(&self, scrutinee: &ast::Expr, arms: &[ast::Arm]) -> FormatResult {
         self.has_vstruct(VStruct::Match, || {
             self.control_flow_header("match", scrutinee)?;
-            self.block(true, arms, |arm| self.arm(arm))?;
+            self.match_arms(arms)?;
             Ok(())
         })
     }
 
+    /// Formats the arms of a match expression after the opening brace
+    pub fn match_arms(&self, arms: &[ast::Arm]) -> FormatResult {
+        self.block(true, arms, |arm| self.arm(arm))
+    }
+
     fn arm(&self, arm: &ast::Arm) -> FormatResult {
         self.with_attrs_tail(
             &arm.attrs,
@@ -82,6 +82,11 @@ impl AstFormatter {
                     tail,
                 )?;
             }
+            // a postfix match is always multi-line
+            ast::ExprKind::Match(..) => {
+                items(before_last)?;
+                self.postfix_item_tail(last, tail)?;
+            }
             // other postfix expression kinds are not overflowable
             _ => {
                 items(chain)?;
@@ -261,11 +266,31 @@ impl AstFormatter {
                 self.out.token("await")?;
                 postfix_tail(self)?;
             }
+            ast::ExprKind::Yield(ast::YieldKind::Postfix(_)) => {
+                self.out.token(".")?;
+                self.out.token("yield")?;
+                postfix_tail(self)?;
+            }
             ast::ExprKind::Field(_, ident) => {
                 self.out.token(".")?;
                 self.ident(ident)?;
                 postfix_tail(self)?;
             }
+            ast::ExprKind::Match(_, ref arms, _) => {
+                self.has_vstruct(VStruct::Match, || {
+                    self.out.token(".")?;
+                    self.out.token_space("match")?;
+                    self.out.token("{")?;
+                    self.match_arms(arms)?;
+                    Ok(())
+                })?;
+                postfix_tail(self)?;
+            }
+            ast::ExprKind::Use(..) => {
+                self.out.token(".")?;
+                self.out.token("use")?;
+                postfix_tail(self)?;
+            }
             ast::ExprKind::MethodCall(ref method_call) => {
                 self.out.token(".")?;
                 self.path_segment(
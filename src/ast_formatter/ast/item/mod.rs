@@ -11,6 +11,7 @@ use crate::ast_formatter::list::options::{
 use crate::ast_formatter::tail::Tail;
 use crate::ast_formatter::width_thresholds::WIDTH_THRESHOLDS;
 use crate::error::{FormatErrorKind, FormatResult};
+use crate::util::cell_ext::CellExt;
 use crate::whitespace::VerticalWhitespaceMode;
 use rustc_ast::ast;
 use rustc_span::Symbol;
@@ -46,6 +47,12 @@ impl AstFormatter {
         kind: impl FnOnce(&K) -> FormatResult,
     ) -> FormatResult {
         self.with_attrs(&item.attrs, item.span.into(), || {
+            let inner_attrs = item.attrs.iter().filter(|attr| {
+                attr.style == ast::AttrStyle::Inner && !attr.is_doc_comment()
+            });
+            let _guard = self.inner_attrs.replace_guard(Vec::from_iter(
+                inner_attrs.cloned(),
+            ));
             self.vis(&item.vis)?;
             kind(&item.kind)?;
             Ok(())
@@ -73,8 +80,7 @@ impl AstFormatter {
             ast::ItemKind::MacCall(ref mac_call) => {
                 self.macro_call(mac_call, MacCallSemi::Item, None)?;
             }
-            // todo
-            ast::ItemKind::MacroDef(..) => return Err(self.err(FormatErrorKind::UnsupportedSyntax)),
+            ast::ItemKind::MacroDef(ident, ref def) => self.macro_def(ident, def)?,
             ast::ItemKind::Mod(safety, ident, ref mod_kind) => {
                 self.mod_item(safety, ident, mod_kind)?
             }
@@ -108,7 +114,7 @@ impl AstFormatter {
         Ok(())
     }
 
-    fn vis(&self, vis: &ast::Visibility) -> FormatResult {
+    pub fn vis(&self, vis: &ast::Visibility) -> FormatResult {
         match vis.kind {
             ast::VisibilityKind::Public => {
                 self.out.token_space("pub")?;
@@ -1,18 +1,21 @@
 use crate::ast_formatter::AstFormatter;
+use crate::ast_formatter::ast::token_trees::is_same_token_stream;
 use crate::ast_formatter::brackets::Brackets;
+use crate::ast_formatter::inline_format_args::inline_format_args;
 use crate::ast_formatter::list::options::{
     FlexibleListStrategy, HorizontalListStrategy, ListOptions, ListStrategies, VerticalListStrategy,
     WrapToFit,
 };
-use crate::ast_formatter::std_macro::std_macro;
+use crate::ast_formatter::std_macro::{MacroOrigin, StdMacro, std_macro};
 use crate::ast_formatter::tail::Tail;
 use crate::ast_formatter::width_thresholds::WIDTH_THRESHOLDS;
+use crate::config::Edition;
 use crate::error::FormatResult;
-use crate::macro_args::{MacroArgs, mac_call_id};
-use crate::span::Span;
+use crate::macro_args::{Bitflags, CfgIfBranch, LazyStatic, MacroArgs, mac_call_id};
+use crate::span::{Span, get_span};
 use crate::whitespace::VerticalWhitespaceMode;
 use rustc_ast::ast;
-use rustc_ast::token::Delimiter;
+use std::cell::Cell;
 use std::num::NonZero;
 
 #[derive(Clone, Copy)]
@@ -31,17 +34,33 @@ impl AstFormatter {
     ) -> FormatResult {
         self.path(&mac_call.path, true)?;
         self.out.token("!")?;
-        let mac_args = self.module.macro_args.get(&mac_call_id(mac_call));
-        let brackets = if let Some(std_macro) = std_macro(mac_call) {
-            std_macro.brackets()
+        let name = mac_call.path.segments.last().unwrap().ident.name;
+        // #[rustfmt::skip::macros(..)]
+        let is_skipped = self.is_skipped_macro(name);
+        let std_macro = if is_skipped {
+            None
         } else {
-            match mac_call.args.delim {
-                Delimiter::Brace => Brackets::Curly,
-                Delimiter::Bracket => Brackets::Square,
-                Delimiter::Parenthesis => Brackets::Parens,
-                Delimiter::Invisible(_) => panic!("unexpected Invisible delimiter"),
+            std_macro(mac_call, &self.config.macros)
+        };
+        let mut is_verbatim = is_skipped || matches!(std_macro, Some((StdMacro::Verbatim, _)));
+        let mac_args = if is_verbatim {
+            None
+        } else {
+            self.module.macro_args.get(&mac_call_id(mac_call))
+        };
+        let std_macro = match std_macro {
+            // the arguments did not parse as the configured kind, so treat it as unknown
+            Some((_, MacroOrigin::Configured)) if mac_args.is_none() => None,
+            // a macro with the same name as a crate macro may have different syntax
+            Some((_, MacroOrigin::Crate)) if mac_args.is_none() => {
+                is_verbatim = true;
+                None
             }
+            std_macro => std_macro.map(|(std_macro, _)| std_macro),
         };
+        let brackets = std_macro
+            .and_then(StdMacro::brackets)
+            .unwrap_or_else(|| Brackets::from_delimiter(mac_call.args.delim));
         if brackets == Brackets::Curly {
             self.out.space()?;
         }
@@ -56,8 +75,12 @@ impl AstFormatter {
             self.out.token_replace(brackets.end())?;
             tail(self)?;
         } else if let Some(args) = mac_args {
-            self.macro_args(brackets, args, Some(&self.tail_fn(tail)))?;
-        } else {
+            if std_macro.is_none() {
+                self.unknown_macro_args(mac_call, brackets, args, tail)?;
+            } else {
+                self.macro_args(mac_call, brackets, args, false, Some(&self.tail_fn(tail)))?;
+            }
+        } else if is_verbatim {
             let dspan = mac_call.args.dspan;
             // skip open bracket but include closing bracket
             let span = Span {
@@ -66,36 +89,159 @@ impl AstFormatter {
             };
             self.out.copy_span(span)?;
             tail(self)?;
+        } else {
+            self.macro_token_trees(&mac_call.args, brackets)?;
+            tail(self)?;
         }
         Ok(())
     }
 
-    fn macro_args(&self, brackets: Brackets, args: &MacroArgs, tail: Tail) -> FormatResult {
+    /// Formats the arguments of an unknown macro like a call. If that would change the token stream
+    /// received by the macro, the arguments are copied from source instead.
+    fn unknown_macro_args(
+        &self,
+        mac_call: &ast::MacCall,
+        brackets: Brackets,
+        args: &MacroArgs,
+        tail: impl Fn(&Self) -> FormatResult,
+    ) -> FormatResult {
+        // skip open bracket but include closing bracket
+        let (lo, hi) = (
+            mac_call.args.dspan.open.hi(),
+            mac_call.args.dspan.close.hi(),
+        );
+        let checkpoint = self.out.checkpoint();
+        let start = self.out.len();
+        let end = Cell::new(start);
+        // an unknown macro may not accept a trailing comma
+        self.macro_args(
+            mac_call,
+            brackets,
+            args,
+            true,
+            Some(&self.tail_fn(|af| {
+                end.set(af.out.len());
+                tail(af)
+            })),
+        )?;
+        let source = get_span(self.out.source_reader.source(), Span { lo, hi });
+        let is_same_token_stream = self.out.with_output_since(start, |out| {
+            is_same_token_stream(source, &out[..end.get() - start])
+        });
+        if !is_same_token_stream {
+            self.out.restore_checkpoint(&checkpoint);
+            self.out.copy_span(Span { lo, hi })?;
+            tail(self)?;
+        }
+        Ok(())
+    }
+
+    fn macro_args(
+        &self,
+        mac_call: &ast::MacCall,
+        brackets: Brackets,
+        args: &MacroArgs,
+        preserve_trailing_comma: bool,
+        tail: Tail,
+    ) -> FormatResult {
         match *args {
-            MacroArgs::Cfg(ref args) => {
-                self.macro_args_list(brackets, args, None, tail, |af, item, tail| {
+            MacroArgs::Asm(ref args) => self.macro_args_list(
+                brackets,
+                args,
+                None,
+                preserve_trailing_comma,
+                tail,
+                |af, arg, tail, _index| af.asm_arg(arg, tail),
+            )?,
+            MacroArgs::Bitflags(ref bitflags) => {
+                self.enclosed_contents(|| {
+                    self.lines(bitflags, |bitflags| self.bitflags(bitflags))
+                })?;
+                self.out.token_replace(brackets.end())?;
+                self.tail(tail)?;
+            }
+            MacroArgs::Cfg(ref args) => self.macro_args_list(
+                brackets,
+                args,
+                None,
+                preserve_trailing_comma,
+                tail,
+                |af, item, tail, _index| {
                     af.meta_item_inner(item)?;
                     af.tail(tail)?;
                     Ok(())
-                })?
-            }
-            MacroArgs::FnLike(ref args) => {
-                self.macro_args_list(brackets, args, None, tail, |af, expr, tail| {
-                    af.expr_tail(expr, tail)
-                })?
+                },
+            )?,
+            MacroArgs::CfgIf(ref branches) => {
+                self.enclosed_contents(|| self.cfg_if(branches))?;
+                self.out.token_replace(brackets.end())?;
+                self.tail(tail)?;
             }
-            MacroArgs::Format {
-                ref args,
-                format_string_pos,
-            } => self.macro_args_list(
+            MacroArgs::FnLike(ref args) => self.macro_args_list(
                 brackets,
                 args,
-                Some(format_string_pos),
+                None,
+                preserve_trailing_comma,
                 tail,
-                |af, item, tail| af.expr_tail(item, tail),
+                |af, expr, tail, _index| af.expr_tail(expr, tail),
             )?,
-            MacroArgs::Matches(ref expr, ref pat, ref guard) => {
-                self.matches_args(expr, pat, guard.as_deref(), tail)?
+            MacroArgs::Format {
+                ref args,
+                format_string_pos,
+                has_target,
+            } => {
+                let format_string_pos = format_string_pos + u8::from(has_target);
+                let inlined = if self.can_inline_format_args(mac_call) {
+                    inline_format_args(
+                        self.out.source_reader.source(),
+                        args,
+                        format_string_pos.into(),
+                    )
+                } else {
+                    None
+                };
+                let args = match inlined {
+                    None => &args[..],
+                    Some(_) => &args[..=usize::from(format_string_pos)],
+                };
+                self.macro_args_list(
+                    brackets,
+                    args,
+                    Some(format_string_pos),
+                    preserve_trailing_comma,
+                    tail,
+                    |af, item, tail, index| {
+                        if has_target && index == 0 {
+                            af.out.token("target")?;
+                            af.out.token_space(":")?;
+                        }
+                        match &inlined {
+                            Some(inlined) if index == usize::from(format_string_pos) => {
+                                af.out.replace_literal(item.span.into(), &inlined.format_string)?;
+                                af.out.skip_removed_span(inlined.removed.into());
+                                af.tail(tail)
+                            }
+                            _ => af.expr_tail(item, tail),
+                        }
+                    },
+                )?
+            }
+            MacroArgs::Items(ref items) => {
+                self.enclosed_contents(|| {
+                    self.list_with_item_sorting(items, |item| self.item(item))
+                })?;
+                self.out.token_replace(brackets.end())?;
+                self.tail(tail)?;
+            }
+            MacroArgs::LazyStatic(ref statics) => {
+                self.enclosed_contents(|| {
+                    self.lines(statics, |lazy_static| self.lazy_static(lazy_static))
+                })?;
+                self.out.token_replace(brackets.end())?;
+                self.tail(tail)?;
+            }
+            MacroArgs::Matches(ref expr, ref pat, ref guard, ref args) => {
+                self.matches_args(expr, pat, guard.as_deref(), args, tail)?
             }
             MacroArgs::ThreadLocal(ref stmts) => {
                 self.enclosed_contents(|| {
@@ -109,20 +255,33 @@ impl AstFormatter {
         Ok(())
     }
 
+    /// Before the 2021 edition, `panic!` and similar macros do not treat a single string literal
+    /// argument as a format string, so inlining all of the arguments would change the message
+    fn can_inline_format_args(&self, mac_call: &ast::MacCall) -> bool {
+        if !self.config.inline_format_args {
+            return false;
+        }
+        let name = mac_call.path.segments.last().unwrap().ident.as_str();
+        let is_panic = matches!(name, "assert" | "debug_assert" | "panic" | "unreachable");
+        !is_panic || self.config.edition >= Edition::Edition2021
+    }
+
     fn macro_args_list<T>(
         &self,
         brackets: Brackets,
         args: &[T],
         format_string_pos: Option<u8>,
+        preserve_trailing_comma: bool,
         tail: Tail,
-        format: impl Fn(&Self, &T, Tail) -> FormatResult,
+        format: impl Fn(&Self, &T, Tail, usize) -> FormatResult,
     ) -> FormatResult {
         self.list(
             brackets,
             args,
-            |af, item, tail, _lcx| format(af, item, tail),
+            |af, item, tail, lcx| format(af, item, tail, lcx.index),
             ListOptions {
                 omit_open_bracket: true,
+                preserve_trailing_comma,
                 strategies: ListStrategies::Flexible(FlexibleListStrategy {
                     horizontal: HorizontalListStrategy {
                         contents_max_width: Some(WIDTH_THRESHOLDS.fn_call_width),
@@ -156,6 +315,7 @@ impl AstFormatter {
         expr: &ast::Expr,
         pat: &ast::Pat,
         guard: Option<&ast::Expr>,
+        args: &[Box<ast::Expr>],
         tail: Tail,
     ) -> FormatResult {
         self.backtrack()
@@ -169,6 +329,10 @@ impl AstFormatter {
                     self.out.space_token_space("if")?;
                     self.expr(guard)?;
                 }
+                for arg in args {
+                    self.out.token_space(",")?;
+                    self.expr(arg)?;
+                }
                 drop(width_limit_guard);
                 self.out.token_skip_if_present(",")?;
                 self.out.token_replace(")")?;
@@ -210,6 +374,11 @@ impl AstFormatter {
                     } else {
                         self.out.token_maybe_missing(",")?;
                     }
+                    for arg in args {
+                        self.out.newline_indent(VerticalWhitespaceMode::Break)?;
+                        self.expr(arg)?;
+                        self.out.token_maybe_missing(",")?;
+                    }
                 }
                 self.out.newline_indent(VerticalWhitespaceMode::Break)?;
                 self.out.token_replace(")")?;
@@ -219,6 +388,68 @@ impl AstFormatter {
             .result()
     }
 
+    fn bitflags(&self, bitflags: &Bitflags) -> FormatResult {
+        self.with_attrs(&bitflags.attrs, bitflags.span.into(), || {
+            self.vis(&bitflags.vis)?;
+            self.out.token_space(if bitflags.is_impl { "impl" } else { "struct" })?;
+            self.ident(bitflags.ident)?;
+            self.out.token_space(":")?;
+            self.ty(&bitflags.ty)?;
+            self.out.space()?;
+            self.block(false, &bitflags.flags, |flag| {
+                self.with_attrs(&flag.attrs, flag.span.into(), || {
+                    self.out.token_space("const")?;
+                    self.ident(flag.ident)?;
+                    self.assign_expr(&flag.expr, Some(&self.tail_token(";")))?;
+                    Ok(())
+                })
+            })?;
+            Ok(())
+        })
+    }
+
+    fn cfg_if(&self, branches: &[CfgIfBranch]) -> FormatResult {
+        for (i, branch) in branches.iter().enumerate() {
+            if i > 0 {
+                self.out.space_token_space("else")?;
+            }
+            if let Some(cfg) = &branch.cfg {
+                self.out.token_space("if")?;
+                self.out.token("#")?;
+                self.out.token("[")?;
+                self.meta_item(cfg)?;
+                self.out.token("]")?;
+                self.out.space()?;
+            }
+            self.block_with_item_sorting(false, &branch.items, |item| self.item(item))?;
+        }
+        Ok(())
+    }
+
+    fn lazy_static(&self, lazy_static: &LazyStatic) -> FormatResult {
+        self.with_attrs(&lazy_static.attrs, lazy_static.span.into(), || {
+            self.vis(&lazy_static.vis)?;
+            self.out.token_space("static")?;
+            self.out.token_space("ref")?;
+            self.ident(lazy_static.ident)?;
+            self.out.token_space(":")?;
+            self.ty(&lazy_static.ty)?;
+            self.assign_expr(&lazy_static.expr, Some(&self.tail_token(";")))?;
+            Ok(())
+        })
+    }
+
+    /// Formats each element on its own line
+    fn lines<T>(&self, list: &[T], format: impl Fn(&T) -> FormatResult) -> FormatResult {
+        for (i, item) in list.iter().enumerate() {
+            if i > 0 {
+                self.out.newline_indent(VerticalWhitespaceMode::Between)?;
+            }
+            format(item)?;
+        }
+        Ok(())
+    }
+
     fn macro_call_semi(&self, semi: MacCallSemi, brackets: Brackets) -> FormatResult {
         match (semi, brackets) {
             (MacCallSemi::Item, Brackets::Curly) => {
@@ -0,0 +1,139 @@
+use crate::ast_formatter::{AstFormatter, format_snippet};
+use crate::config::{NewlineStyle, Severity};
+use crate::error::{FormatErrorKind, FormatResult};
+use crate::parse::{ParseSnippetResult, parse_snippet};
+use crate::snippet::SnippetKind;
+use crate::span::{Span, get_span};
+use rustc_ast::ast;
+use rustc_ast::token::{Delimiter, TokenKind};
+use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_lexer::is_id_start;
+use rustc_span::symbol::Ident;
+use std::rc::Rc;
+
+/// Stands in for `$` in a transcriber so that `$name` parses as an identifier. It has the same
+/// display width as `$`.
+const METAVAR_PLACEHOLDER: char = 'ζ';
+
+struct MacroRulesArm {
+    matcher: DelimSpan,
+    transcriber: DelimSpan,
+}
+
+impl AstFormatter {
+    pub fn macro_def(&self, ident: Ident, def: &ast::MacroDef) -> FormatResult {
+        if !def.macro_rules {
+            return Err(self.err(FormatErrorKind::UnsupportedSyntax));
+        }
+        self.out.token("macro_rules")?;
+        self.out.token("!")?;
+        self.out.space()?;
+        self.ident(ident)?;
+        self.out.space()?;
+        let body_span = def.body.dspan.entire().into();
+        let arms = if def.body.delim == Delimiter::Brace {
+            macro_rules_arms(&def.body.tokens)
+        } else {
+            None
+        };
+        let Some(arms) = arms else {
+            self.out.copy_span(body_span)?;
+            if def.body.delim != Delimiter::Brace {
+                self.out.token_maybe_missing(";")?;
+            }
+            return Ok(());
+        };
+        self.block(false, &arms, |arm| {
+            self.out.copy_span(arm.matcher.entire().into())?;
+            self.out.space_token_space("=>")?;
+            self.macro_rules_transcriber(arm.transcriber)?;
+            self.out.token_maybe_missing(";")?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Formats the transcriber as a block if it parses as one, or else copies it
+    fn macro_rules_transcriber(&self, transcriber: DelimSpan) -> FormatResult {
+        let inner = Span {
+            lo: transcriber.open.hi(),
+            hi: transcriber.close.lo(),
+        };
+        let inner = get_span(self.out.source_reader.source(), inner);
+        match self.format_transcriber(inner) {
+            Some(formatted) => {
+                self.out.replace_span(transcriber.entire().into(), &formatted)
+            }
+            None => self.out.copy_span(transcriber.entire().into()),
+        }
+    }
+
+    fn format_transcriber(&self, inner: &str) -> Option<String> {
+        if inner.contains(METAVAR_PLACEHOLDER) {
+            return None;
+        }
+        let source = format!("{{{}}}", replace_metavars(inner));
+        let ParseSnippetResult {
+            snippet,
+            module,
+            source_file,
+        } = parse_snippet(&source, SnippetKind::Block, &self.config.macros, true).ok()?;
+        // Errors are reported and line endings are converted when the output is written
+        let config = self
+            .config
+            .clone()
+            .max_width(self.out.constraints().max_width.get())
+            .newline_style(NewlineStyle::Unix)
+            .line_comment_not_allowed(Severity::Allow)
+            .max_width_exceeded(Severity::Allow)
+            .multi_line_comment_not_allowed(Severity::Allow)
+            .unsupported_syntax(Severity::Allow);
+        let indent = self.out.total_indent.get();
+        let result = format_snippet(&snippet, Rc::new(module), source_file, &config, indent);
+        Some(result.formatted.replace(METAVAR_PLACEHOLDER, "$"))
+    }
+}
+
+/// Splits the body of `macro_rules! name { .. }` into `(matcher) => { transcriber };` arms
+fn macro_rules_arms(tokens: &TokenStream) -> Option<Vec<MacroRulesArm>> {
+    let mut iter = tokens.iter();
+    let mut arms = Vec::new();
+    while let Some(tt) = iter.next() {
+        let TokenTree::Delimited(matcher, ..) = *tt else {
+            return None;
+        };
+        let TokenTree::Token(ref token, _) = *iter.next()? else {
+            return None;
+        };
+        if token.kind != TokenKind::FatArrow {
+            return None;
+        }
+        let TokenTree::Delimited(transcriber, ..) = *iter.next()? else {
+            return None;
+        };
+        arms.push(MacroRulesArm {
+            matcher,
+            transcriber,
+        });
+        match iter.next() {
+            None => break,
+            Some(TokenTree::Token(token, _)) if token.kind == TokenKind::Semi => {}
+            Some(_) => return None,
+        }
+    }
+    Some(arms)
+}
+
+/// Replaces `$` with a placeholder where it starts a metavariable like `$name`
+fn replace_metavars(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' && chars.peek().is_some_and(|&next| is_id_start(next)) {
+            out.push(METAVAR_PLACEHOLDER);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -1,3 +1,4 @@
+mod asm;
 mod attr;
 mod block;
 mod common;
@@ -7,6 +8,8 @@ mod generics;
 pub mod item;
 mod local;
 mod r#macro;
+mod macro_def;
 mod pat;
 mod path;
+mod token_trees;
 mod ty;
@@ -0,0 +1,222 @@
+use crate::ast_formatter::AstFormatter;
+use crate::ast_formatter::INDENT_WIDTH;
+use crate::ast_formatter::brackets::Brackets;
+use crate::error::FormatResult;
+use crate::num::HSize;
+use crate::span::{Span, get_span};
+use crate::util::chars::char_width;
+use rustc_ast::ast;
+use rustc_ast::token::TokenKind;
+use rustc_ast::tokenstream::{DelimSpan, TokenStream, TokenTree};
+use rustc_lexer::FrontmatterAllowed;
+use rustc_span::BytePos;
+
+impl AstFormatter {
+    /// Formats the arguments of a macro that is not otherwise recognized, along with the closing
+    /// bracket. Only whitespace between tokens is changed. If the token stream would be altered in
+    /// any way, the arguments are copied from source instead.
+    pub fn macro_token_trees(&self, args: &ast::DelimArgs, brackets: Brackets) -> FormatResult {
+        let (lo, hi) = (args.dspan.open.hi(), args.dspan.close.hi());
+        let source = self.out.source_reader.source();
+        let mut renderer = TokenTreeRenderer {
+            source,
+            max_width: self.out.constraints().max_width.get(),
+            out: String::new(),
+            col: self.out.col().into(),
+        };
+        renderer.group_contents(
+            brackets,
+            args.dspan,
+            &args.tokens,
+            self.out.total_indent.get(),
+        );
+        if is_same_token_stream(get_span(source, Span { lo, hi }), &renderer.out) {
+            self.out.replace_span(Span { lo, hi }, &renderer.out)
+        } else {
+            self.out.copy_span(Span { lo, hi })
+        }
+    }
+}
+
+struct TokenTreeRenderer<'a> {
+    source: &'a str,
+    max_width: HSize,
+    out: String,
+    /// The display width of the last line of `out`, plus the starting column
+    col: usize,
+}
+
+impl TokenTreeRenderer<'_> {
+    /// Renders the contents of a delimited group and its closing delimiter. Stays on one line if
+    /// the group is on one line in source and fits. Otherwise, each line is indented one level
+    /// deeper than `line_indent`, the indentation of the line with the opening delimiter.
+    fn group_contents(
+        &mut self,
+        brackets: Brackets,
+        dspan: DelimSpan,
+        tokens: &TokenStream,
+        line_indent: HSize,
+    ) {
+        if tokens.is_empty() {
+            self.push_str(brackets.end());
+            return;
+        }
+        let trees = Vec::from_iter(tokens.iter());
+        let is_multi_line = self.gap(dspan.open.hi(), dspan.close.lo()).contains('\n');
+        if !is_multi_line {
+            let mut single_line = TokenTreeRenderer {
+                source: self.source,
+                max_width: self.max_width,
+                out: String::new(),
+                col: self.col,
+            };
+            let pad = if brackets.pad() { " " } else { "" };
+            single_line.push_str(pad);
+            single_line.trees(&trees, line_indent);
+            single_line.push_str(pad);
+            single_line.push_str(brackets.end());
+            if !single_line.out.contains('\n') && single_line.col <= usize::from(self.max_width) {
+                self.push_str(&single_line.out);
+                return;
+            }
+        }
+        let indent = line_indent + INDENT_WIDTH;
+        let mut line_start = 0;
+        for (i, pair) in trees.windows(2).enumerate() {
+            let (prev, next) = (pair[0], pair[1]);
+            let newlines = if is_multi_line {
+                self.gap(prev.span().hi(), next.span().lo())
+                    .matches('\n')
+                    .count()
+            } else {
+                usize::from(matches!(
+                    prev,
+                    TokenTree::Token(token, _) if matches!(
+                        token.kind,
+                        TokenKind::Comma | TokenKind::Semi,
+                    ),
+                ))
+            };
+            if newlines > 0 {
+                self.newline_indent(indent);
+                self.trees(&trees[line_start..=i], indent);
+                if newlines > 1 {
+                    self.push_str("\n");
+                }
+                line_start = i + 1;
+            }
+        }
+        self.newline_indent(indent);
+        self.trees(&trees[line_start..], indent);
+        self.newline_indent(line_indent);
+        self.push_str(brackets.end());
+    }
+
+    /// Renders token trees on one line, except for any delimited groups that need to be broken
+    fn trees(&mut self, trees: &[&TokenTree], line_indent: HSize) {
+        for (i, tree) in trees.iter().enumerate() {
+            if i > 0 && self.space_between(trees[i - 1], tree) {
+                self.push_str(" ");
+            }
+            match tree {
+                TokenTree::Token(token, _) => {
+                    self.push_str(get_span(self.source, token.span.into()))
+                }
+                TokenTree::Delimited(dspan, _, delim, tokens) => {
+                    let brackets = Brackets::from_delimiter(*delim);
+                    self.push_str(brackets.start());
+                    self.group_contents(brackets, *dspan, tokens, line_indent);
+                }
+            }
+        }
+    }
+
+    fn space_between(&self, prev: &TokenTree, next: &TokenTree) -> bool {
+        let has_space = || !self.gap(prev.span().hi(), next.span().lo()).is_empty();
+        let kind = |tree: &TokenTree| match tree {
+            TokenTree::Token(token, _) => Some(token.kind),
+            TokenTree::Delimited(..) => None,
+        };
+        let is_punct =
+            |tree: &TokenTree| matches!(tree, TokenTree::Token(token, _) if token.is_punct());
+        // adjacent punctuation may be joined into one operator
+        if is_punct(prev) && is_punct(next) {
+            return has_space();
+        }
+        match (kind(prev), kind(next)) {
+            (_, Some(TokenKind::Comma | TokenKind::Semi)) => false,
+            (Some(TokenKind::Comma | TokenKind::Semi), _) => true,
+            (Some(TokenKind::Dot | TokenKind::PathSep), _)
+            | (_, Some(TokenKind::Dot | TokenKind::PathSep)) => false,
+            _ => has_space(),
+        }
+    }
+
+    fn gap(&self, lo: BytePos, hi: BytePos) -> &str {
+        get_span(self.source, Span { lo, hi })
+    }
+
+    fn newline_indent(&mut self, indent: HSize) {
+        self.out.push('\n');
+        self.out.extend(std::iter::repeat_n(' ', indent.into()));
+        self.col = indent.into();
+    }
+
+    fn push_str(&mut self, str: &str) {
+        self.out.push_str(str);
+        match str.rfind('\n') {
+            Some(i) => self.col = str[i + 1..].chars().map(char_width).sum(),
+            None => self.col += str.chars().map(char_width).sum::<usize>(),
+        }
+    }
+}
+
+/// Checks that two strings have the same tokens, including comments, and that punctuation is
+/// joined in the same places
+pub fn is_same_token_stream(a: &str, b: &str) -> bool {
+    fn tokens(source: &str) -> impl Iterator<Item = (&str, bool)> {
+        let mut pos = 0;
+        let mut prev_is_punct = false;
+        rustc_lexer::tokenize(source, FrontmatterAllowed::No).filter_map(move |token| {
+            let text = &source[pos..pos + token.len as usize];
+            pos += token.len as usize;
+            if token.kind == rustc_lexer::TokenKind::Whitespace {
+                prev_is_punct = false;
+                return None;
+            }
+            let is_punct = is_punct(token.kind);
+            let is_joint = prev_is_punct && is_punct;
+            prev_is_punct = is_punct;
+            Some((text, is_joint))
+        })
+    }
+    tokens(a).eq(tokens(b))
+}
+
+fn is_punct(kind: rustc_lexer::TokenKind) -> bool {
+    use rustc_lexer::TokenKind::*;
+    matches!(
+        kind,
+        Semi
+        | Comma
+        | Dot
+        | At
+        | Pound
+        | Tilde
+        | Question
+        | Colon
+        | Dollar
+        | Eq
+        | Bang
+        | Lt
+        | Gt
+        | Minus
+        | And
+        | Or
+        | Plus
+        | Star
+        | Slash
+        | Caret
+        | Percent,
+    )
+}
@@ -1,3 +1,5 @@
+use rustc_ast::token::Delimiter;
+
 macro_rules! define_brackets {
     ($($name:ident($start:literal, $end:literal, $pad:literal),)*) => {
         #[derive(Clone, Copy, Debug, PartialEq)]
@@ -35,3 +37,14 @@ define_brackets! {
     Pipe("|", "|", false),
     Square("[", "]", false),
 }
+
+impl Brackets {
+    pub fn from_delimiter(delim: Delimiter) -> Brackets {
+        match delim {
+            Delimiter::Brace => Brackets::Curly,
+            Delimiter::Bracket => Brackets::Square,
+            Delimiter::Parenthesis => Brackets::Parens,
+            Delimiter::Invisible(_) => panic!("unexpected Invisible delimiter"),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::span::get_span;
+use crate::util::whitespace_utils::contains_comment;
+use rustc_ast::ast;
+use rustc_ast::token;
+use rustc_parse_format::{Count, ParseMode, Parser, Piece, Position};
+
+/// The result of inlining format arguments, like `println!("{}", x)` to `println!("{x}")`
+pub struct InlinedFormatArgs {
+    /// The format string literal with the arguments inlined
+    pub format_string: String,
+    /// The source after the format string that is removed, like `, x`
+    pub removed: rustc_span::Span,
+}
+
+/// Inlines all arguments after the format string if each one is a plain identifier used exactly
+/// once by a positional placeholder. Returns None if any argument cannot be inlined.
+pub fn inline_format_args(
+    source: &str,
+    args: &[Box<ast::Expr>],
+    format_string_pos: usize,
+) -> Option<InlinedFormatArgs> {
+    let (format_string, args) = args.get(format_string_pos..)?.split_first()?;
+    if args.is_empty() {
+        return None;
+    }
+    let ast::ExprKind::Lit(lit) = format_string.kind else {
+        return None;
+    };
+    let style = match lit.kind {
+        token::LitKind::Str => None,
+        token::LitKind::StrRaw(hashes) => Some(usize::from(hashes)),
+        _ => return None,
+    };
+    let ast::LitKind::Str(symbol, _) = ast::LitKind::from_token_lit(lit).ok()? else {
+        return None;
+    };
+    let names = args
+        .iter()
+        .map(|arg| arg_ident_name(source, arg))
+        .collect::<Option<Vec<_>>>()?;
+    let snippet = get_span(source, format_string.span.into());
+    let mut parser = Parser::new(
+        symbol.as_str(),
+        style,
+        Some(snippet.to_owned()),
+        false,
+        ParseMode::Format,
+    );
+    let pieces = Vec::from_iter(parser.by_ref());
+    if !parser.errors.is_empty() || !parser.is_source_literal {
+        return None;
+    }
+    let mut uses = Vec::from_iter(std::iter::repeat_n(0, args.len()));
+    let mut replacements = Vec::new();
+    for piece in &pieces {
+        let Piece::NextArgument(arg) = piece else {
+            continue;
+        };
+        // a width or precision that refers to an argument would be shifted
+        let refers_to_arg =
+            |count: &Count<'_>| matches!(count, Count::CountIsParam(_) | Count::CountIsStar(_));
+        if refers_to_arg(&arg.format.width) || refers_to_arg(&arg.format.precision) {
+            return None;
+        }
+        let index = match arg.position {
+            Position::ArgumentImplicitlyIs(index) | Position::ArgumentIs(index) => index,
+            Position::ArgumentNamed(_) => continue,
+        };
+        *uses.get_mut(index)? += 1;
+        replacements.push((arg.position_span.clone(), names[index]));
+    }
+    if uses.iter().any(|&count| count != 1) {
+        return None;
+    }
+    let removed = format_string.span.between(
+        args.last().unwrap().span.shrink_to_hi(),
+    );
+    if contains_comment(get_span(source, removed.into())) {
+        return None;
+    }
+    replacements.sort_by_key(|(range, _)| range.start);
+    let mut format_string = String::with_capacity(snippet.len());
+    let mut pos = 0;
+    for (range, name) in replacements {
+        format_string.push_str(&snippet[pos..range.start]);
+        format_string.push_str(name);
+        pos = range.end;
+    }
+    format_string.push_str(&snippet[pos..]);
+    Some(InlinedFormatArgs {
+        format_string,
+        removed,
+    })
+}
+
+/// Gets the name of an argument that is a plain identifier and can be written in a format string
+fn arg_ident_name<'a>(source: &'a str, arg: &ast::Expr) -> Option<&'a str> {
+    let ast::ExprKind::Path(None, path) = &arg.kind else {
+        return None;
+    };
+    let [segment] = &path.segments[..] else {
+        return None;
+    };
+    if segment.args.is_some() || segment.ident.is_reserved() {
+        return None;
+    }
+    let name = get_span(source, segment.ident.span.into());
+    // excludes raw identifiers like `r#type`
+    (name == segment.ident.as_str()).then_some(name)
+}
@@ -154,6 +154,8 @@ where
         let last_item_tail = af.tail_fn(|af| {
             if rest.is_some() || opt.force_trailing_comma {
                 af.out.token(",")?;
+            } else if opt.preserve_trailing_comma {
+                af.out.token_if_present(",")?;
             } else {
                 af.out.token_skip_if_present(",")?;
             }
@@ -310,7 +312,11 @@ where
             for index in rest {
                 let item_comma = || -> FormatResult {
                     item(index)?;
-                    af.out.token_maybe_missing(",")?;
+                    if opt.preserve_trailing_comma && index == list.len() - 1 {
+                        af.out.token_if_present(",")?;
+                    } else {
+                        af.out.token_maybe_missing(",")?;
+                    }
                     Ok(())
                 };
                 let is_own_line = prev_must_have_own_line
@@ -381,7 +387,18 @@ where
             match opt.rest {
                 None => {
                     items(0..len - 1)?;
-                    item_comma(len - 1)?;
+                    if opt.preserve_trailing_comma {
+                        self.list_item(
+                            len - 1,
+                            None,
+                            Some(&af.tail_fn(|af| {
+                                af.out.token_if_present(",")?;
+                                Ok(())
+                            })),
+                        )?;
+                    } else {
+                        item_comma(len - 1)?;
+                    }
                 }
                 Some(rest) => {
                     items(0..len)?;
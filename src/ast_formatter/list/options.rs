@@ -106,6 +106,8 @@ pub struct ListOptions<'ast, 'tail, Item> {
     pub force_trailing_comma: bool = false,
     pub is_struct: bool = false,
     pub omit_open_bracket: bool = false,
+    /// Only write a trailing comma if it is in the source
+    pub preserve_trailing_comma: bool = false,
     pub rest: Option<ListRest<'ast>> = None,
     pub strategies: ListStrategies<'ast, Item> = default(),
     pub tail: Tail<'tail, 'ast> = None,
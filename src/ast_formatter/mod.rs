@@ -1,20 +1,26 @@
-use rustc_span::SourceFile;
+use rustc_ast::ast::Attribute;
+use rustc_span::{SourceFile, Symbol};
+use std::cell::Cell;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::FormatModuleResult;
 use crate::ast_module::AstModule;
+use crate::ast_utils::is_rustfmt_skip;
 use crate::config::Config;
 use crate::error::{FormatResult, error_formatting_at};
 use crate::error_emitter::{BufferedErrorEmitter, ErrorEmitter};
+use crate::newline_style::{apply_newline_style, original_source};
 use crate::num::HSize;
+use crate::snippet::Snippet;
 use crate::source_formatter::SourceFormatter;
 use crate::whitespace::VerticalWhitespaceMode;
 
 mod ast;
 pub mod backtrack;
 pub mod brackets;
+mod inline_format_args;
 mod list;
 pub mod std_macro;
 pub mod tail;
@@ -29,31 +35,75 @@ pub fn format_module(
     path: Option<PathBuf>,
     config: &Config,
 ) -> FormatModuleResult {
-    let errors = Rc::new(BufferedErrorEmitter::new(ErrorEmitter::new(path.clone())));
-    // todo need Arc?
-    let out = SourceFormatter::new(
-        path,
-        Arc::new(source_file),
-        Rc::clone(&errors),
-        config.max_width,
-    );
-    AstFormatter {
-        module,
-        errors,
-        out,
-    }
-    .module()
+    let original = original_source(&source_file);
+    let mut result =
+        AstFormatter::new(module, source_file, path, config).format(|af| af.do_module());
+    apply_newline_style(config.newline_style, &mut result.formatted, &original);
+    result
+}
+
+/// Formats a snippet as if it is positioned at the given indentation.
+///
+/// The output does not include indentation before the first line.
+pub fn format_snippet(
+    snippet: &Snippet,
+    module: Rc<AstModule>,
+    source_file: SourceFile,
+    config: &Config,
+    indent: HSize,
+) -> FormatModuleResult {
+    let original = original_source(&source_file);
+    let af = AstFormatter::new(module, source_file, None, config);
+    af.out.total_indent.set(indent);
+    let mut result = af.format(|af| af.do_snippet(snippet));
+    result.formatted.drain(..usize::from(indent));
+    apply_newline_style(config.newline_style, &mut result.formatted, &original);
+    result
 }
 
 struct AstFormatter {
     module: Rc<AstModule>,
+    config: Config,
     errors: Rc<BufferedErrorEmitter>,
+    /// Inner attributes of the innermost enclosing item, except doc comments. These are written at
+    /// the start of the item's body.
+    inner_attrs: Cell<Vec<Attribute>>,
     out: SourceFormatter,
+    /// Names from `#[rustfmt::skip::attributes(..)]` on enclosing nodes
+    skip_attributes: Cell<Vec<Symbol>>,
+    /// Names from `#[rustfmt::skip::macros(..)]` on enclosing nodes
+    skip_macros: Cell<Vec<Symbol>>,
 }
 
 impl AstFormatter {
-    fn module(self) -> FormatModuleResult {
-        match self.do_module() {
+    fn new(
+        module: Rc<AstModule>,
+        source_file: SourceFile,
+        path: Option<PathBuf>,
+        config: &Config,
+    ) -> AstFormatter {
+        let errors = Rc::new(BufferedErrorEmitter::new(ErrorEmitter::new(path.clone(), config)));
+        // todo need Arc?
+        let out = SourceFormatter::new(
+            path,
+            Arc::new(source_file),
+            Rc::clone(&errors),
+            config.max_width,
+            config.error_on_line_overflow,
+        );
+        AstFormatter {
+            module,
+            config: config.clone(),
+            errors,
+            inner_attrs: Cell::new(Vec::new()),
+            out,
+            skip_attributes: Cell::new(Vec::new()),
+            skip_macros: Cell::new(Vec::new()),
+        }
+    }
+
+    fn format(self, scope: impl FnOnce(&Self) -> FormatResult) -> FormatModuleResult {
+        match scope(&self) {
             Err(e) => {
                 // todo don't panic?
                 // todo make it possible to panic inside ErrorEmitter instead?
@@ -71,9 +121,13 @@ impl AstFormatter {
             }
             Ok(()) => {
                 let Self {
+                    config: _,
                     errors,
+                    inner_attrs: _,
                     out,
                     module: _,
+                    skip_attributes: _,
+                    skip_macros: _,
                 } = self;
                 let formatted = out.finish();
                 let error_count = Rc::into_inner(errors).unwrap().finish();
@@ -92,9 +146,13 @@ impl AstFormatter {
             items,
             ..
         } = &*self.module;
+        if attrs.iter().any(is_rustfmt_skip) {
+            self.out.copy_remaining();
+            return Ok(());
+        }
         self.out.comments(VerticalWhitespaceMode::Top)?;
-        // todo skip the whole file if there's a skip attribute?
         self.with_attrs(attrs, spans.inner_span.into(), || {
+            attrs.iter().try_for_each(|attr| self.attr(attr))?;
             self.list_with_item_sorting(items, |item| self.item(item))
         })?;
         if !items.is_empty() {
@@ -103,6 +161,20 @@ impl AstFormatter {
         Ok(())
     }
 
+    fn do_snippet(&self, snippet: &Snippet) -> FormatResult {
+        self.out.indent();
+        self.out.comments(VerticalWhitespaceMode::Top)?;
+        match snippet {
+            Snippet::Block(block) => self.block_expr(false, block)?,
+            Snippet::Expr(expr) => self.expr(expr)?,
+            Snippet::Item(item) => self.item(item)?,
+            Snippet::Pat(pat) => self.pat(pat)?,
+            Snippet::Ty(ty) => self.ty(ty)?,
+        }
+        self.out.comments(VerticalWhitespaceMode::Bottom)?;
+        Ok(())
+    }
+
     // todo use or delete
     // todo make it a macro? looks innocuous
     #[allow(unused)]
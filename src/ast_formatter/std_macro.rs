@@ -1,37 +1,116 @@
 use crate::ast_formatter::brackets::Brackets;
-use rustc_ast::ast;
+use crate::config::{MacroKind, MacroTable};
+use rustc_ast::{AsmMacro, ast};
+use rustc_span::symbol::{kw, sym};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum StdMacro {
+    Asm(AsmMacro),
+    /// `assert_matches!` and `debug_assert_matches!`, which are like `matches!` followed by
+    /// optional format arguments
+    AssertMatches,
+    /// `bitflags!` from the bitflags crate
+    Bitflags,
     Cfg,
+    /// `cfg_if!` from the cfg-if crate
+    CfgIf,
     FnLike,
     Format { format_string_pos: u8 },
+    Items,
+    /// `lazy_static!` from the lazy_static crate
+    LazyStatic,
     Matches,
     ThreadLocal,
     Vec,
+    /// Arguments are copied from source since the output depends on spacing between tokens
+    Verbatim,
 }
 
 impl StdMacro {
-    pub fn brackets(self) -> Brackets {
-        match self {
-            StdMacro::Cfg | StdMacro::FnLike | StdMacro::Format { .. } | StdMacro::Matches => {
-                Brackets::Parens
-            }
-            StdMacro::ThreadLocal => Brackets::Curly,
+    /// Returns None if the brackets are kept from source
+    pub fn brackets(self) -> Option<Brackets> {
+        let brackets = match self {
+            StdMacro::Asm(_)
+            | StdMacro::AssertMatches
+            | StdMacro::Cfg
+            | StdMacro::FnLike
+            | StdMacro::Format { .. }
+            | StdMacro::Matches => Brackets::Parens,
+            StdMacro::Bitflags
+            | StdMacro::CfgIf
+            | StdMacro::Items
+            | StdMacro::LazyStatic
+            | StdMacro::ThreadLocal => Brackets::Curly,
             StdMacro::Vec => Brackets::Square,
+            StdMacro::Verbatim => return None,
+        };
+        Some(brackets)
+    }
+}
+
+impl From<MacroKind> for StdMacro {
+    fn from(kind: MacroKind) -> Self {
+        match kind {
+            MacroKind::FnLike => StdMacro::FnLike,
+            MacroKind::Format { format_string_pos } => StdMacro::Format { format_string_pos },
+            MacroKind::Items => StdMacro::Items,
+            MacroKind::Vec => StdMacro::Vec,
         }
     }
 }
 
-pub fn std_macro(mac_call: &ast::MacCall) -> Option<StdMacro> {
-    let [segment] = &mac_call.path.segments[..] else {
-        return None;
+/// Where the way to format a macro call was found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MacroOrigin {
+    Std,
+    /// The configured macro table. The arguments may not always follow the configured kind.
+    Configured,
+    /// A widely used crate macro, recognized by name only
+    Crate,
+}
+
+/// Finds how to format a macro call, from the configured macro table or else from the known std
+/// macros
+pub fn std_macro(mac_call: &ast::MacCall, macros: &MacroTable) -> Option<(StdMacro, MacroOrigin)> {
+    let segments = match &mac_call.path.segments[..] {
+        [root, rest @ ..] if root.ident.name == kw::PathRoot => rest,
+        segments => segments,
+    };
+    if !macros.0.is_empty() {
+        let path = Vec::from_iter(segments.iter().map(|s| s.ident.as_str())).join("::");
+        let last = segments.last().unwrap().ident.as_str();
+        if let Some(&kind) = macros.0.get(&path).or_else(|| macros.0.get(last)) {
+            return Some((kind.into(), MacroOrigin::Configured));
+        }
+    }
+    let segment = match segments {
+        [segment] => segment,
+        [krate, segment] if matches!(krate.ident.name, sym::alloc | sym::core | sym::std) => {
+            segment
+        }
+        // e.g. `core::arch::asm!`
+        [krate, module, segment]
+            if matches!(krate.ident.name, sym::core | sym::std)
+                && module.ident.as_str() == "arch" =>
+        {
+            segment
+        }
+        // e.g. `cfg_if::cfg_if!`
+        [krate, segment] if krate.ident.name == segment.ident.name => {
+            return crate_macro(segment.ident.as_str()).map(|m| (m, MacroOrigin::Crate));
+        }
+        _ => return None,
     };
+    if let Some(crate_macro) = crate_macro(segment.ident.as_str()) {
+        return Some((crate_macro, MacroOrigin::Crate));
+    }
     // macros with no arguments are not here since they are handled generically
     let std_macro = match segment.ident.as_str() {
+        "asm" => StdMacro::Asm(AsmMacro::Asm),
         "cfg" => StdMacro::Cfg,
         "compile_error"
         | "concat"
+        | "concat_idents"
         | "dbg"
         | "env"
         | "file"
@@ -43,7 +122,8 @@ pub fn std_macro(mac_call: &ast::MacCall) -> Option<StdMacro> {
         | "line"
         | "module_path"
         | "option_env" => StdMacro::FnLike,
-        "eprint"
+        "const_format_args"
+        | "eprint"
         | "eprintln"
         | "format_args"
         | "panic"
@@ -60,10 +140,25 @@ pub fn std_macro(mac_call: &ast::MacCall) -> Option<StdMacro> {
         "assert_eq" | "assert_ne" | "debug_assert_eq" | "debug_assert_ne" => StdMacro::Format {
             format_string_pos: 2,
         },
+        "assert_matches" | "debug_assert_matches" => StdMacro::AssertMatches,
+        "global_asm" => StdMacro::Asm(AsmMacro::GlobalAsm),
         "matches" => StdMacro::Matches,
+        "naked_asm" => StdMacro::Asm(AsmMacro::NakedAsm),
+        "stringify" => StdMacro::Verbatim,
         "thread_local" => StdMacro::ThreadLocal,
         "vec" => StdMacro::Vec,
         _ => return None,
     };
-    Some(std_macro)
+    Some((std_macro, MacroOrigin::Std))
+}
+
+/// Widely used macros from crates that are named after the macro
+fn crate_macro(name: &str) -> Option<StdMacro> {
+    let crate_macro = match name {
+        "bitflags" => StdMacro::Bitflags,
+        "cfg_if" => StdMacro::CfgIf,
+        "lazy_static" => StdMacro::LazyStatic,
+        _ => return None,
+    };
+    Some(crate_macro)
 }
@@ -19,7 +19,12 @@ macro_rules! delegate_to_constraints {
 
 delegate_to_constraints! {
     pub fn err(&self, kind: FormatErrorKind) -> FormatError;
-    pub fn disallow_vstructs(&self, values: impl Into<VStructSet>, recover: &Recover, scope: impl FnOnce() -> FormatResult) -> FormatResult;
+    pub fn disallow_vstructs(
+        &self,
+        values: impl Into<VStructSet>,
+        recover: &Recover,
+        scope: impl FnOnce() -> FormatResult
+    ) -> FormatResult;
     pub fn has_vstruct<T>(&self, vstruct: VStruct, scope: impl FnOnce() -> FormatResult<T>) -> FormatResult<T>;
 }
 
@@ -3,7 +3,7 @@ pub mod use_tree_order;
 pub mod version_sort;
 
 use rustc_ast::ast;
-use rustc_span::sym;
+use rustc_span::{Symbol, sym};
 
 pub fn is_jump_expr(expr: &ast::Expr) -> bool {
     match expr.kind {
@@ -12,20 +12,20 @@ pub fn is_jump_expr(expr: &ast::Expr) -> bool {
         | ast::ExprKind::Continue(..)
         | ast::ExprKind::Ret(..)
         | ast::ExprKind::Yeet(..)
-        | ast::ExprKind::Yield(..) => true,
+        | ast::ExprKind::Yield(ast::YieldKind::Prefix(_)) => true,
         _ => false,
     }
 }
 
 macro_rules! postfix_utils {
-    ($($kind:ident$fields:tt, $receiver:expr, $is_dot:literal,)*) => {
+    ($($kind:ident$shape:tt => $fields:tt, $receiver:expr, $is_dot:literal,)*) => {
         macro_rules! postfix_expr_kind {
-            () => ($(::rustc_ast::ast::ExprKind::$kind(..))|*);
+            () => ($(::rustc_ast::ast::ExprKind::$kind$shape)|*);
         }
         pub(crate) use postfix_expr_kind;
 
         pub fn is_postfix_expr(expr: &ast::Expr) -> bool {
-            matches!(expr.kind, $(::rustc_ast::ast::ExprKind::$kind(..))|*)
+            matches!(expr.kind, $(::rustc_ast::ast::ExprKind::$kind$shape)|*)
         }
 
         /// If the given expression is postfix, returns its receiver expression.
@@ -39,7 +39,7 @@ macro_rules! postfix_utils {
         /// Postfix expressions with a dot can be wrapped to the next line
         pub fn postfix_expr_is_dot(postfix_expr: &ast::Expr) -> bool {
             match postfix_expr.kind {
-                $(::rustc_ast::ast::ExprKind::$kind(..) => $is_dot,)|*
+                $(::rustc_ast::ast::ExprKind::$kind$shape => $is_dot,)|*
                 _ => panic!("expected a postfix expression"),
             }
         }
@@ -47,15 +47,69 @@ macro_rules! postfix_utils {
 }
 postfix_utils! {
     // note: ExprKind::Cast isn't here since it is lower precedence and so it doesn't chain
-    Await(receiver, _),      receiver,              true,
-    Field(receiver, _),      receiver,              true,
-    MethodCall(method_call), &method_call.receiver, true,
-    Index(receiver, _, _),   receiver,              false,
-    Try(receiver),           receiver,              false,
+    // kind(pattern) => (fields binding the receiver), receiver, is_dot
+    Await(..)      => (receiver, _),    receiver,              true,
+    Field(..)      => (receiver, _),    receiver,              true,
+    MethodCall(..) => (method_call),    &method_call.receiver, true,
+    Index(..)      => (receiver, _, _), receiver,              false,
+    Match(_, _, ::rustc_ast::ast::MatchKind::Postfix)
+        => (receiver, _, _), receiver, true,
+    Try(..)        => (receiver),       receiver,              false,
+    Use(..)        => (receiver, _),    receiver,              true,
+    Yield(::rustc_ast::ast::YieldKind::Postfix(_))
+        => (::rustc_ast::ast::YieldKind::Postfix(receiver)), receiver, true,
 }
 
+/// `#[rustfmt::skip]`, or one of the legacy forms `#[rustfmt_skip]` and
+/// `#[cfg_attr(rustfmt, rustfmt_skip)]`
 pub fn is_rustfmt_skip(attr: &ast::Attribute) -> bool {
-    attr.path_matches(&[sym::rustfmt, sym::skip])
+    if attr.path_matches(&[sym::rustfmt, sym::skip])
+        || attr.path_matches(&[Symbol::intern("rustfmt_skip")])
+    {
+        return true;
+    }
+    if !attr.has_name(sym::cfg_attr) {
+        return false;
+    }
+    let Some(list) = attr.meta_item_list() else {
+        return false;
+    };
+    let [predicate, attrs @ ..] = &list[..] else {
+        return false;
+    };
+    predicate.is_word()
+        && predicate.has_name(sym::rustfmt)
+        && attrs.iter().any(|attr| {
+            attr.meta_item().is_some_and(|meta| {
+                meta.is_word()
+                    && (
+                        meta.has_name(Symbol::intern("rustfmt_skip"))
+                            || meta.path == [sym::rustfmt, sym::skip].as_slice()
+                    )
+            })
+        })
+}
+
+/// For `#[rustfmt::skip::<kind>(a, b)]`, returns the names `a` and `b`
+pub fn rustfmt_skip_names(attr: &ast::Attribute, kind: Symbol) -> Vec<Symbol> {
+    if !attr.path_matches(&[sym::rustfmt, sym::skip, kind]) {
+        return Vec::new();
+    }
+    let Some(list) = attr.meta_item_list() else {
+        return Vec::new();
+    };
+    Vec::from_iter(list.iter().filter_map(|item| item.name()))
+}
+
+/// For `#[myrustfmt::allow(a, b)]`, returns the names `a` and `b`
+pub fn myrustfmt_allow_names(attr: &ast::Attribute) -> Vec<Symbol> {
+    if !attr.path_matches(&[Symbol::intern("myrustfmt"), sym::allow]) {
+        return Vec::new();
+    }
+    let Some(list) = attr.meta_item_list() else {
+        return Vec::new();
+    };
+    Vec::from_iter(list.iter().filter_map(|item| item.name()))
 }
 
 // a block with no label, no `async`, no `unsafe`
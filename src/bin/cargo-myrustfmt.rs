@@ -0,0 +1,99 @@
+#![feature(rustc_private)]
+
+use getopts::Options;
+use myrustfmt::cargo_workspace::{Package, Workspace};
+use myrustfmt::config::Edition;
+use myrustfmt::format_module_file_roots;
+use myrustfmt::options::{add_shared_options, config_from_matches};
+use std::env;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = Vec::from_iter(env::args().skip(1));
+    // `cargo myrustfmt` invokes this binary with "myrustfmt" as the first argument
+    if args.first().is_some_and(|arg| arg == "myrustfmt") {
+        args.remove(0);
+    }
+    let options = build_options();
+    let options_matches = match options.parse(&args) {
+        Ok(matches) => matches,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if options_matches.opt_present("help") {
+        print!("{}", options.usage("Usage: cargo myrustfmt [options]"));
+        return ExitCode::SUCCESS;
+    }
+    let is_check = options_matches.opt_present("check");
+    let is_verbose = options_matches.opt_present("verbose");
+    let config = match config_from_matches(&options_matches) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if options_matches.opt_present("all") && options_matches.opt_present("package") {
+        eprintln!("--all cannot be used with --package");
+        return ExitCode::FAILURE;
+    }
+    let current_dir = env::current_dir().expect("failed to get the current directory");
+    let workspace = match Workspace::find(&current_dir) {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let package_names = options_matches.opt_strs("package");
+    let packages: Vec<&Package> = if options_matches.opt_present("all") {
+        workspace.packages.iter().collect()
+    } else if !package_names.is_empty() {
+        let mut packages = Vec::new();
+        for name in &package_names {
+            let Some(package) = workspace.package_by_name(name) else {
+                eprintln!("Package `{name}` is not a member of the workspace");
+                return ExitCode::FAILURE;
+            };
+            packages.push(package);
+        }
+        packages
+    } else {
+        workspace
+            .current
+            .iter()
+            .map(|&index| &workspace.packages[index])
+            .collect()
+    };
+    let mut is_ok = true;
+    for package in packages {
+        if is_verbose {
+            eprintln!("Package: {}", package.name);
+        }
+        let mut config = config.clone();
+        // --edition overrides the edition of every package
+        if config.edition == Edition::Unknown {
+            config.edition = package.edition;
+        }
+        let paths = package.crate_roots.clone();
+        if format_module_file_roots(paths, config, is_check, is_verbose).is_err() {
+            is_ok = false;
+        }
+    }
+    if is_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn build_options() -> Options {
+    let mut opts = Options::new();
+    opts.optflag("h", "help", "Print this help message");
+    opts.optflag("", "all", "Format all packages in the workspace");
+    opts.optmulti("p", "package", "Package to format", "PACKAGE");
+    add_shared_options(&mut opts);
+    opts
+}
@@ -0,0 +1,331 @@
+//! Finds the crate roots of a Cargo workspace by reading its Cargo.toml files. This does not use
+//! `cargo metadata`, so it works offline and without building anything.
+
+use crate::config::Edition;
+use crate::util::glob::wildcard_match;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use toml::{Table, Value};
+
+const MANIFEST: &str = "Cargo.toml";
+
+pub struct Package {
+    pub name: String,
+    pub dir: PathBuf,
+    /// From `package.edition`, which is 2015 when it is not set
+    pub edition: Edition,
+    pub crate_roots: Vec<PathBuf>,
+}
+
+pub struct Workspace {
+    pub packages: Vec<Package>,
+    /// Indices into `packages` for the manifest nearest to the starting directory. This is one
+    /// package, or every member of a virtual workspace.
+    pub current: Vec<usize>,
+}
+
+impl Workspace {
+    /// Finds the workspace that contains the given directory
+    pub fn find(dir: &Path) -> Result<Workspace, Box<dyn Error>> {
+        let dir = dir.canonicalize().map_err(|e| {
+            format!("failed to resolve {}: {e}", dir.display())
+        })?;
+        let manifest_dir = dir
+            .ancestors()
+            .find(|dir| dir.join(MANIFEST).is_file())
+            .ok_or_else(|| format!("could not find {MANIFEST} in {}", dir.display()))?;
+        let manifest = read_manifest(manifest_dir)?;
+        let (root_dir, root_manifest) = if manifest.contains_key("workspace") {
+            (manifest_dir.to_path_buf(), manifest)
+        } else {
+            match find_workspace_root(manifest_dir)? {
+                Some((root_dir, root_manifest)) => (root_dir, root_manifest),
+                None => {
+                    let packages = Vec::from_iter(read_package(manifest_dir, &manifest, None)?);
+                    let current = Vec::from_iter(0..packages.len());
+                    return Ok(Workspace { packages, current });
+                }
+            }
+        };
+        let mut packages = Vec::new();
+        packages.extend(read_package(&root_dir, &root_manifest, Some(&root_manifest))?);
+        for member_dir in workspace_members(&root_dir, &root_manifest)? {
+            let member_manifest = read_manifest(&member_dir)?;
+            packages.extend(read_package(&member_dir, &member_manifest, Some(&root_manifest))?);
+        }
+        let current = match packages.iter().position(|p| p.dir == manifest_dir) {
+            Some(index) => vec![index],
+            None => Vec::from_iter(0..packages.len()),
+        };
+        Ok(Workspace { packages, current })
+    }
+
+    pub fn package_by_name(&self, name: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+}
+
+/// Returns the edition of the package with the manifest nearest to the given file, which does not
+/// need to exist. Returns None if there is no such manifest or it is a virtual manifest.
+pub fn find_package_edition(path: &Path) -> Result<Option<Edition>, Box<dyn Error>> {
+    let path = std::path::absolute(path)?;
+    let Some(manifest_dir) = path.ancestors().skip(1).find(|dir| {
+        dir.join(MANIFEST).is_file()
+    }) else {
+        return Ok(None);
+    };
+    let workspace = Workspace::find(manifest_dir)?;
+    let manifest_dir = manifest_dir.canonicalize()?;
+    let package = workspace.packages.into_iter().find(|p| {
+        p.dir == manifest_dir
+    });
+    Ok(package.map(|p| p.edition))
+}
+
+/// Searches parent directories for a workspace that includes the package in `package_dir`
+fn find_workspace_root(package_dir: &Path) -> Result<Option<(PathBuf, Table)>, Box<dyn Error>> {
+    for dir in package_dir.ancestors().skip(1) {
+        if !dir.join(MANIFEST).is_file() {
+            continue;
+        }
+        let manifest = read_manifest(dir)?;
+        if !manifest.contains_key("workspace") {
+            continue;
+        }
+        let members = workspace_members(dir, &manifest)?;
+        if members.iter().any(|member| member == package_dir) {
+            return Ok(Some((dir.to_path_buf(), manifest)));
+        }
+        return Ok(None);
+    }
+    Ok(None)
+}
+
+fn workspace_members(root_dir: &Path, manifest: &Table) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let Some(workspace) = manifest.get("workspace").and_then(Value::as_table) else {
+        return Ok(Vec::new());
+    };
+    let exclude = BTreeSet::from_iter(strings(workspace, "exclude")?.into_iter().map(|path| {
+        normalize(&root_dir.join(path))
+    }));
+    let mut members = BTreeSet::new();
+    for pattern in strings(workspace, "members")? {
+        for dir in expand_glob(root_dir, pattern) {
+            let dir = normalize(&dir);
+            if dir != root_dir && !exclude.contains(&dir) && dir.join(MANIFEST).is_file() {
+                members.insert(dir);
+            }
+        }
+    }
+    Ok(Vec::from_iter(members))
+}
+
+fn read_manifest(dir: &Path) -> Result<Table, Box<dyn Error>> {
+    let path = dir.join(MANIFEST);
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let manifest = contents.parse::<Table>().map_err(|e| {
+        format!("failed to parse {}: {e}", path.display())
+    })?;
+    Ok(manifest)
+}
+
+/// Returns None for a virtual manifest. Fields inherited with `workspace = true` are read from
+/// `workspace_manifest`.
+fn read_package(
+    dir: &Path,
+    manifest: &Table,
+    workspace_manifest: Option<&Table>,
+) -> Result<Option<Package>, Box<dyn Error>> {
+    let Some(package) = manifest.get("package").and_then(Value::as_table) else {
+        return Ok(None);
+    };
+    let name = package
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing package name in {}", dir.join(MANIFEST).display()))?
+        .to_owned();
+    let edition = package_edition(dir, package, workspace_manifest)?;
+    let mut crate_roots = BTreeSet::new();
+
+    let lib = manifest.get("lib").and_then(Value::as_table);
+    match lib.and_then(|lib| lib.get("path")).and_then(Value::as_str) {
+        Some(path) => {
+            crate_roots.insert(dir.join(path));
+        }
+        None => crate_roots.extend(existing(dir.join("src/lib.rs"))),
+    }
+
+    match package.get("build") {
+        Some(Value::String(path)) => {
+            crate_roots.insert(dir.join(path));
+        }
+        Some(_) => {}
+        None => crate_roots.extend(existing(dir.join("build.rs"))),
+    }
+
+    let is_auto = |key: &str| package.get(key).and_then(Value::as_bool).unwrap_or(true);
+    if is_auto("autobins") {
+        crate_roots.extend(existing(dir.join("src/main.rs")));
+    }
+    for (key, auto_key, target_dir) in [
+        ("bin", "autobins", "src/bin"),
+        ("test", "autotests", "tests"),
+        ("example", "autoexamples", "examples"),
+        ("bench", "autobenches", "benches"),
+    ] {
+        let target_dir = dir.join(target_dir);
+        for target in targets(manifest, key)? {
+            if let Some(path) = target.get("path").and_then(Value::as_str) {
+                crate_roots.insert(dir.join(path));
+                continue;
+            }
+            let Some(target_name) = target.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            let candidates = [
+                target_dir.join(format!("{target_name}.rs")),
+                target_dir.join(target_name).join("main.rs"),
+            ];
+            let found = candidates.into_iter().find(|path| path.is_file());
+            match found {
+                Some(path) => {
+                    crate_roots.insert(path);
+                }
+                None if key == "bin" && target_name == name => {
+                    crate_roots.extend(existing(dir.join("src/main.rs")));
+                }
+                None => {}
+            }
+        }
+        if is_auto(auto_key) {
+            crate_roots.extend(discover_targets(&target_dir));
+        }
+    }
+
+    Ok(Some(Package {
+        name,
+        dir: dir.to_path_buf(),
+        edition,
+        crate_roots: Vec::from_iter(crate_roots),
+    }))
+}
+
+fn package_edition(
+    dir: &Path,
+    package: &Table,
+    workspace_manifest: Option<&Table>,
+) -> Result<Edition, Box<dyn Error>> {
+    let manifest_path = || dir.join(MANIFEST).display().to_string();
+    let value = match package.get("edition") {
+        None => return Ok(Edition::Edition2015),
+        Some(Value::Table(table)) if table.get("workspace") == Some(&Value::Boolean(true)) => {
+            workspace_manifest
+                .and_then(|manifest| manifest.get("workspace")?.get("package")?.get("edition"))
+                .ok_or_else(|| {
+                    format!(
+                        "missing workspace.package.edition inherited by {}",
+                        manifest_path(),
+                    )
+                })?
+        }
+        Some(value) => value,
+    };
+    let edition = value
+        .as_str()
+        .ok_or_else(|| format!("expected a string for `edition` in {}", manifest_path()))?
+        .parse::<Edition>()
+        .map_err(|e| format!("{e} in {}", manifest_path()))?;
+    Ok(edition)
+}
+
+fn targets<'a>(manifest: &'a Table, key: &str) -> Result<Vec<&'a Table>, Box<dyn Error>> {
+    let Some(value) = manifest.get(key) else {
+        return Ok(Vec::new());
+    };
+    let targets = value
+        .as_array()
+        .and_then(|targets| {
+            targets
+                .iter()
+                .map(Value::as_table)
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| format!("expected an array of tables for `{key}`"))?;
+    Ok(targets)
+}
+
+/// Finds `<dir>/*.rs` and `<dir>/*/main.rs`
+fn discover_targets(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            paths.extend(existing(path.join("main.rs")));
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+fn strings<'a>(table: &'a Table, key: &str) -> Result<Vec<&'a str>, Box<dyn Error>> {
+    let Some(value) = table.get(key) else {
+        return Ok(Vec::new());
+    };
+    let strings = value
+        .as_array()
+        .and_then(|values| values.iter().map(Value::as_str).collect::<Option<Vec<_>>>())
+        .ok_or_else(|| format!("expected an array of strings for `{key}`"))?;
+    Ok(strings)
+}
+
+fn existing(path: PathBuf) -> Option<PathBuf> {
+    path.is_file().then_some(path)
+}
+
+/// Expands a workspace member pattern, where `*` and `?` may be used within each path component
+fn expand_glob(root_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root_dir.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        if !component.contains(['*', '?']) {
+            for dir in &mut dirs {
+                dir.push(component);
+            }
+            continue;
+        }
+        dirs = dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|entry| entry.path().is_dir())
+            .filter(|entry| {
+                entry.file_name().to_str().is_some_and(|name| {
+                    wildcard_match(component, name)
+                })
+            })
+            .map(|entry| entry.path())
+            .collect();
+    }
+    dirs
+}
+
+/// Removes `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -1,9 +1,14 @@
 use crate::num::HSize;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::str::FromStr;
+use toml::{Table, Value};
 
 macro_rules! config {
-    ($($name:ident: $ty:ty = $default:expr,)*) => {
+    ($($(#[doc = $doc:literal])* $name:ident: $ty:ty = $default:expr,)*) => {
+        #[derive(Clone)]
         pub struct Config {
-            $(pub $name: $ty,)*
+            $($(#[doc = $doc])* pub $name: $ty,)*
         }
 
         impl Default for Config {
@@ -30,5 +35,180 @@ macro_rules! config {
 }
 
 config! {
+    /// The Rust edition of the formatted code. Some rewrites are only done when it is known.
+    edition: Edition = Edition::Unknown,
+    /// Report an error when a line exceeds `max_width`. A line is not reported if it is too long
+    /// only because of a literal that starts right after the indentation, like a long URL.
+    error_on_line_overflow: bool = true,
+    /// Format files marked with `@generated` or `DO NOT EDIT` in their leading comments
+    format_generated_files: bool = false,
+    /// Files matching any of these globs are not formatted
+    ignore: GlobList = GlobList::default(),
+    /// Rewrite format macro calls like `println!("{}", x)` to `println!("{x}")` when every argument
+    /// after the format string is an identifier that is used once
+    inline_format_args: bool = false,
+    /// How to report a line comment where the formatting requires everything on one line
+    line_comment_not_allowed: Severity = Severity::Error,
+    /// How to format the arguments of macros by name, in addition to known std macros
+    macros: MacroTable = MacroTable::default(),
+    /// Keep a copy of each file before overwriting it, named like `main.rs.bk`
+    make_backup: bool = false,
     max_width: HSize = 100,
+    /// How to report a line that exceeds `max_width`
+    max_width_exceeded: Severity = Severity::Error,
+    /// How to report a multi-line block comment where the formatting requires everything on one
+    /// line
+    multi_line_comment_not_allowed: Severity = Severity::Error,
+    newline_style: NewlineStyle = NewlineStyle::Auto,
+    /// Do not format submodules found in other files
+    skip_children: bool = false,
+    /// How to report syntax that cannot be formatted and is copied from source
+    unsupported_syntax: Severity = Severity::Error,
+}
+
+/// A list of glob patterns. When parsed from a string, the patterns are separated by commas.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlobList(pub Vec<String>);
+
+impl FromStr for GlobList {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let globs = s
+            .split(',')
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .map(String::from);
+        Ok(GlobList(Vec::from_iter(globs)))
+    }
+}
+
+/// The line endings of formatted output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NewlineStyle {
+    /// Use the line ending of the first line in the original source
+    Auto,
+    /// `\r\n` on Windows and `\n` elsewhere
+    Native,
+    Unix,
+    Windows,
+}
+
+impl FromStr for NewlineStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Auto" => Ok(NewlineStyle::Auto),
+            "Native" => Ok(NewlineStyle::Native),
+            "Unix" => Ok(NewlineStyle::Unix),
+            "Windows" => Ok(NewlineStyle::Windows),
+            _ => Err(format!("Invalid newline style: {s}")),
+        }
+    }
+}
+
+/// A Rust edition
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Edition {
+    Unknown,
+    Edition2015,
+    Edition2018,
+    Edition2021,
+    Edition2024,
+}
+
+impl FromStr for Edition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2015" => Ok(Edition::Edition2015),
+            "2018" => Ok(Edition::Edition2018),
+            "2021" => Ok(Edition::Edition2021),
+            "2024" => Ok(Edition::Edition2024),
+            _ => Err(format!("Invalid edition: {s}")),
+        }
+    }
+}
+
+/// How a kind of error is reported
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// Not reported
+    Allow,
+    /// Reported without failing
+    Warn,
+    Error,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Severity::Allow),
+            "warn" => Ok(Severity::Warn),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!("Invalid severity: {s}")),
+        }
+    }
+}
+
+/// How to format the arguments of a macro
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MacroKind {
+    /// Like a function call
+    FnLike,
+    /// Like `format!`, with the format string at the given position. The arguments may start
+    /// with `target: <expr>,` as in the `log` crate.
+    Format { format_string_pos: u8 },
+    /// Items in curly braces
+    Items,
+    /// Like `vec![..]`
+    Vec,
+}
+
+/// Macro kinds by name. A name matches either the whole path of a macro call, like
+/// `tracing::event`, or its last segment. When parsed from a string, this is a TOML table like
+/// `info = { kind = "format", format_string_pos = 0 }`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MacroTable(pub BTreeMap<String, MacroKind>);
+
+impl FromStr for MacroTable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s
+            .parse::<Table>()
+            .map_err(|e| format!("Invalid macro table: {e}"))?;
+        let mut macros = BTreeMap::new();
+        for (name, value) in table {
+            let kind = macro_kind(&value)
+                .ok_or_else(|| format!("Invalid macro table entry for `{name}`: {value}"))?;
+            macros.insert(name, kind);
+        }
+        Ok(MacroTable(macros))
+    }
+}
+
+fn macro_kind(value: &Value) -> Option<MacroKind> {
+    let entry = value.as_table()?;
+    let format_string_pos = match entry.get("format_string_pos") {
+        None => None,
+        Some(pos) => Some(u8::try_from(pos.as_integer()?).ok()?),
+    };
+    let kind = match entry.get("kind")?.as_str()? {
+        "fn_like" => MacroKind::FnLike,
+        "format" => MacroKind::Format {
+            format_string_pos: format_string_pos.unwrap_or(0),
+        },
+        "items" => MacroKind::Items,
+        "vec" => MacroKind::Vec,
+        _ => return None,
+    };
+    if format_string_pos.is_some() && !matches!(kind, MacroKind::Format { .. }) {
+        return None;
+    }
+    Some(kind)
 }
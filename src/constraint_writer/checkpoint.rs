@@ -7,7 +7,9 @@ pub struct ConstraintWriterCheckpoint {
     buffer_len: usize,
     constraints_checkpoint: ConstraintsCheckpoint,
     line: VSize,
+    line_continued_from: VSize,
     last_line_start: usize,
+    last_line_width_excess: usize,
     last_width_exceeded_line: Option<VSize>,
 }
 
@@ -16,15 +18,19 @@ impl ConstraintWriter {
         let Self {
             ref constraints,
             ref last_line_start,
+            ref last_line_width_excess,
             ref last_width_exceeded_line,
             ref line,
+            ref line_continued_from,
             ..
         } = *self;
         ConstraintWriterCheckpoint {
             buffer_len: self.len(),
             constraints_checkpoint: constraints.checkpoint(),
             line: line.get(),
+            line_continued_from: line_continued_from.get(),
             last_line_start: last_line_start.get(),
+            last_line_width_excess: last_line_width_excess.get(),
             last_width_exceeded_line: last_width_exceeded_line.get(),
         }
     }
@@ -34,13 +40,17 @@ impl ConstraintWriter {
             buffer_len,
             ref constraints_checkpoint,
             last_line_start,
+            last_line_width_excess,
             last_width_exceeded_line,
             line,
+            line_continued_from,
         } = *checkpoint;
         self.buffer.with_taken(|b| b.truncate(buffer_len));
         self.constraints.restore_checkpoint(constraints_checkpoint);
         self.last_line_start.set(last_line_start);
+        self.last_line_width_excess.set(last_line_width_excess);
         self.last_width_exceeded_line.set(last_width_exceeded_line);
         self.line.set(line);
+        self.line_continued_from.set(line_continued_from);
     }
 }
@@ -5,6 +5,7 @@ use crate::error::{FormatErrorKind, FormatResult, VerticalError, WidthLimitExcee
 use crate::error_emitter::BufferedErrorEmitter;
 use crate::num::{HSize, VSize};
 use crate::util::cell_ext::CellExt;
+use crate::util::chars::{char_width, width_excess};
 use std::cell::Cell;
 use std::panic::Location;
 use std::rc::Rc;
@@ -13,14 +14,21 @@ pub struct ConstraintWriter {
     constraints: Constraints,
     buffer: Cell<String>,
     errors: Rc<BufferedErrorEmitter>,
+    error_on_line_overflow: bool,
     last_line_start: Cell<usize>,
+    /// The number of bytes in the last line beyond its display width, from non-ASCII characters
+    last_line_width_excess: Cell<usize>,
     last_width_exceeded_line: Cell<Option<VSize>>,
     line: Cell<VSize>,
+    /// If the last line ends a multi-line token, this is the line where the token started.
+    /// Otherwise, it is the last line.
+    line_continued_from: Cell<VSize>,
 }
 
 impl ConstraintWriter {
     pub fn new(
         max_width: HSize,
+        error_on_line_overflow: bool,
         errors: Rc<BufferedErrorEmitter>,
         capacity: usize,
     ) -> ConstraintWriter {
@@ -28,9 +36,12 @@ impl ConstraintWriter {
             constraints: Constraints::new(max_width),
             buffer: Cell::new(String::with_capacity(capacity)),
             errors,
+            error_on_line_overflow,
             last_line_start: Cell::new(0),
+            last_line_width_excess: Cell::new(0),
             last_width_exceeded_line: Cell::new(None),
             line: Cell::new(0),
+            line_continued_from: Cell::new(0),
         }
     }
 
@@ -50,9 +61,10 @@ impl ConstraintWriter {
         self.line.get()
     }
 
+    /// The display width of the last line
     // todo make sure any math using two values of this are guaranteed to be on the same line
     pub fn col(&self) -> HSize {
-        (self.len() - self.last_line_start.get())
+        (self.len() - self.last_line_start.get() - self.last_line_width_excess.get())
             .try_into()
             .expect("line length exceeds HSize::MAX")
     }
@@ -61,11 +73,21 @@ impl ConstraintWriter {
         (self.line(), self.col())
     }
 
+    /// The number of characters in the last line. Errors use this rather than `col` so that their
+    /// columns match those of other tools.
+    pub fn char_col(&self) -> HSize {
+        self.with_last_line(|line| line.chars().count())
+            .try_into()
+            .expect("line length exceeds HSize::MAX")
+    }
+
     pub fn is_enforcing_width(&self) -> bool {
         if self.constraints.width_limit_end_col(self.line()).is_some() {
             return true;
         }
-        if self.constraints.recover_width.get() == Some(self.line()) {
+        if let Some(recover_width) = self.constraints.recover_width.get()
+            && (recover_width == self.line() || recover_width == self.line_continued_from.get())
+        {
             return true;
         }
         false
@@ -74,23 +96,51 @@ impl ConstraintWriter {
     /// A token must not have newlines
     pub fn token(&self, token: &str) -> FormatResult {
         self.buffer.with_taken(|b| b.push_str(token));
+        self.last_line_width_excess.update(|n| n + width_excess(token));
         self.check_width_constraints()
     }
 
+    /// Writes a token like a string literal that cannot be broken up. If it starts right after the
+    /// indentation, exceeding the max width is not an error since nothing can be done about it.
+    pub fn unbreakable_token(&self, token: &str) -> FormatResult {
+        self.buffer.with_taken(|b| b.push_str(token));
+        self.last_line_width_excess.update(|n| n + width_excess(token));
+        self.check_width_constraints_after_token(Some(token))
+    }
+
     pub fn write_str(&self, source: &str) -> FormatResult {
         for c in source.chars() {
             if c == '\n' {
                 self.newline()?;
             } else {
                 self.buffer.with_taken(|b| b.push(c));
+                if !c.is_ascii() {
+                    self.last_line_width_excess.update(|n| n + c.len_utf8() - char_width(c));
+                }
                 self.check_width_constraints()?;
             }
         }
         Ok(())
     }
 
+    /// Writes a token that may have newlines, like a multi-line string literal. The lines after the
+    /// first cannot be changed by formatting, so they are allowed in a single-line context. Only the
+    /// last line is checked for width since it is followed by more code.
+    pub fn multi_line_token(&self, token: &str) -> FormatResult {
+        let mut lines = token.split('\n');
+        self.token(lines.next().unwrap())?;
+        let first_line = self.line_continued_from.get();
+        for line in lines {
+            self.push_newline();
+            self.write_str_unchecked(line);
+        }
+        self.line_continued_from.set(first_line);
+        self.check_width_constraints()
+    }
+
     pub fn write_str_unchecked(&self, str: &str) {
         self.buffer.with_taken(|b| b.push_str(str));
+        self.last_line_width_excess.update(|n| n + width_excess(str));
     }
 
     pub fn newline(&self) -> FormatResult {
@@ -99,10 +149,16 @@ impl ConstraintWriter {
                 self.constraints.err(FormatErrorKind::Vertical(VerticalError::Newline)),
             );
         }
+        self.push_newline();
+        Ok(())
+    }
+
+    fn push_newline(&self) {
         self.buffer.with_taken(|b| b.push('\n'));
         self.last_line_start.set(self.len());
+        self.last_line_width_excess.set(0);
         self.line.update(|n| n + 1);
-        Ok(())
+        self.line_continued_from.set(self.line.get());
     }
 
     pub fn spaces(&self, count: HSize) {
@@ -110,6 +166,10 @@ impl ConstraintWriter {
     }
 
     pub fn check_width_constraints(&self) -> FormatResult {
+        self.check_width_constraints_after_token(None)
+    }
+
+    fn check_width_constraints_after_token(&self, token: Option<&str>) -> FormatResult {
         if self.require_remaining_width().is_ok() {
             return Ok(());
         }
@@ -121,12 +181,20 @@ impl ConstraintWriter {
             let line = self.line.get();
             if self.last_width_exceeded_line.get() != Some(line) {
                 self.last_width_exceeded_line.set(Some(line));
-                self.errors.max_width_exceeded(line);
+                let is_unbreakable = token.is_some_and(|token| self.is_first_on_line(token));
+                if self.error_on_line_overflow && !is_unbreakable {
+                    self.errors.max_width_exceeded(line);
+                }
             }
             Ok(())
         }
     }
 
+    /// Returns true if the token was just written after only indentation on the last line
+    fn is_first_on_line(&self, token: &str) -> bool {
+        self.with_last_line(|line| line[..line.len() - token.len()].bytes().all(|b| b == b' '))
+    }
+
     pub fn require_remaining_width(&self) -> Result<HSize, WidthLimitExceededError> {
         self.constraints.require_remaining_width(self.line(), self.col())
     }
@@ -135,6 +203,11 @@ impl ConstraintWriter {
         self.buffer.with_taken(|b| f(&b[self.last_line_start.get()..]))
     }
 
+    /// Calls `f` with the output from the given position to the end
+    pub fn with_output_since<T>(&self, pos: usize, f: impl FnOnce(&str) -> T) -> T {
+        self.buffer.with_taken(|b| f(&b[pos..]))
+    }
+
     pub fn with_taken_buffer(&self, f: impl FnOnce(&mut String)) {
         self.buffer.with_taken(f)
     }
@@ -1,6 +1,9 @@
+use crate::config::{Config, Severity};
 use crate::num::{HSize, VSize};
 use crate::util::cell_ext::CellExt;
+use enumset::{EnumSet, EnumSetType};
 use std::cell::Cell;
+use std::fmt;
 use std::path::PathBuf;
 
 #[derive(Debug)]
@@ -11,31 +14,72 @@ pub enum Error {
     UnsupportedSyntax { line: VSize, col: HSize },
 }
 
+impl Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::LineCommentNotAllowed { .. } => ErrorKind::LineComment,
+            Error::MaxWidthExceeded { .. } => ErrorKind::MaxWidth,
+            Error::MultiLineCommentNotAllowed { .. } => ErrorKind::MultiLineComment,
+            Error::UnsupportedSyntax { .. } => ErrorKind::UnsupportedSyntax,
+        }
+    }
+}
+
+/// The kinds of errors that may be named in `#[myrustfmt::allow(..)]`
+#[derive(Debug, EnumSetType)]
+pub enum ErrorKind {
+    LineComment,
+    MaxWidth,
+    MultiLineComment,
+    UnsupportedSyntax,
+}
+
+impl ErrorKind {
+    pub fn from_name(name: &str) -> Option<ErrorKind> {
+        match name {
+            "line_comment" => Some(ErrorKind::LineComment),
+            "max_width" => Some(ErrorKind::MaxWidth),
+            "multi_line_comment" => Some(ErrorKind::MultiLineComment),
+            "unsupported_syntax" => Some(ErrorKind::UnsupportedSyntax),
+            _ => None,
+        }
+    }
+}
+
 pub struct BufferedErrorEmitter {
+    /// Kinds of errors that are dropped, from `#[myrustfmt::allow(..)]` on enclosing nodes
+    pub allowed: Cell<EnumSet<ErrorKind>>,
     /// Buffered errors. Errors are buffered whenever there are any checkpoints.
     buffer: Cell<Vec<Error>>,
     checkpoint_count: Cell<u32>,
+    /// The number of errors reported, including allowed errors
+    report_count: Cell<u32>,
     emitter: ErrorEmitter,
 }
 
 pub struct Checkpoint {
     buffer_len: usize,
     index: u32,
+    report_count: u32,
 }
 
 impl BufferedErrorEmitter {
     pub fn new(emitter: ErrorEmitter) -> BufferedErrorEmitter {
         BufferedErrorEmitter {
+            allowed: Cell::new(EnumSet::empty()),
             checkpoint_count: Cell::new(0),
             buffer: Cell::new(Vec::new()),
+            report_count: Cell::new(0),
             emitter,
         }
     }
 
     pub fn finish(self) -> u32 {
         let Self {
+            allowed: _,
             checkpoint_count,
             buffer,
+            report_count: _,
             emitter,
         } = self;
         assert_eq!(checkpoint_count.get(), 0);
@@ -43,16 +87,19 @@ impl BufferedErrorEmitter {
         emitter.error_count.get()
     }
 
-    pub fn error_count(&self) -> u32 {
-        let buffer_len = self.buffer.with_taken(|b| b.len());
-        self.emitter.error_count() + u32::try_from(buffer_len).unwrap()
+    pub fn report_count(&self) -> u32 {
+        self.report_count.get()
     }
 
     pub fn checkpoint(&self) -> Checkpoint {
         let buffer_len = self.buffer.with_taken(|b| b.len());
         let index = self.checkpoint_count.get();
         self.checkpoint_count.update(|n| n + 1);
-        Checkpoint { buffer_len, index }
+        Checkpoint {
+            buffer_len,
+            index,
+            report_count: self.report_count.get(),
+        }
     }
 
     pub fn commit_checkpoint(&self, checkpoint: Checkpoint) {
@@ -68,6 +115,7 @@ impl BufferedErrorEmitter {
         self.buffer.with_taken(|buffer| {
             buffer.truncate(checkpoint.buffer_len);
         });
+        self.report_count.set(checkpoint.report_count);
     }
 
     // actual errors
@@ -104,6 +152,10 @@ impl BufferedErrorEmitter {
     }
 
     fn buffer_or_emit(&self, error: Error) {
+        self.report_count.update(|n| n + 1);
+        if self.allowed.get().contains(error.kind()) {
+            return;
+        }
         if self.is_buffering() {
             self.buffer(error);
         } else {
@@ -136,41 +188,75 @@ impl BufferedErrorEmitter {
 pub struct ErrorEmitter {
     error_count: Cell<u32>,
     path: Option<PathBuf>,
+    line_comment_not_allowed: Severity,
+    max_width_exceeded: Severity,
+    multi_line_comment_not_allowed: Severity,
+    unsupported_syntax: Severity,
 }
 
 macro_rules! emit {
-    ($emitter:expr, $($t:tt)*) => {
-        $emitter.error_count.update(|n| n + 1);
-        eprintln!($($t)*);
+    ($emitter:expr, $severity:expr, $($t:tt)*) => {
+        $emitter.emit($severity, format_args!($($t)*))
     };
 }
 
 impl ErrorEmitter {
-    pub fn new(path: Option<PathBuf>) -> ErrorEmitter {
+    pub fn new(path: Option<PathBuf>, config: &Config) -> ErrorEmitter {
         ErrorEmitter {
             error_count: Cell::new(0),
             path,
+            line_comment_not_allowed: config.line_comment_not_allowed,
+            max_width_exceeded: config.max_width_exceeded,
+            multi_line_comment_not_allowed: config.multi_line_comment_not_allowed,
+            unsupported_syntax: config.unsupported_syntax,
         }
     }
 
-    fn error_count(&self) -> u32 {
-        self.error_count.get()
-    }
-
     pub fn line_comment_not_allowed(&self, line: VSize, col: HSize) {
-        emit!(self, "Line comment not allowed{}", self.at(line, col));
+        emit!(
+            self,
+            self.line_comment_not_allowed,
+            "Line comment not allowed{}",
+            self.at(line, col),
+        );
     }
 
     pub fn multi_line_comment_not_allowed(&self, line: VSize, col: HSize) {
-        emit!(self, "Multi-line comment not allowed{}", self.at(line, col));
+        emit!(
+            self,
+            self.multi_line_comment_not_allowed,
+            "Multi-line comment not allowed{}",
+            self.at(line, col),
+        );
     }
 
     pub fn unsupported_syntax(&self, line: VSize, col: HSize) {
-        emit!(self, "Unsupported syntax{}", self.at(line, col));
+        emit!(
+            self,
+            self.unsupported_syntax,
+            "Unsupported syntax{}",
+            self.at(line, col),
+        );
     }
 
     fn width_exceeded(&self, line: VSize) {
-        emit!(self, "Max width exceeded{}", self.at_line(line));
+        emit!(
+            self,
+            self.max_width_exceeded,
+            "Max width exceeded{}",
+            self.at_line(line),
+        );
+    }
+
+    fn emit(&self, severity: Severity, message: fmt::Arguments) {
+        match severity {
+            Severity::Allow => {}
+            Severity::Warn => eprintln!("Warning: {message}"),
+            Severity::Error => {
+                self.error_count.update(|n| n + 1);
+                eprintln!("{message}");
+            }
+        }
     }
 
     fn at(&self, line: VSize, col: HSize) -> String {
@@ -0,0 +1,114 @@
+use crate::config::Config;
+use crate::num::HSize;
+use crate::snippet::SnippetKind;
+use crate::util::rustc::with_rustc_globals;
+use crate::{FormatModuleResult, format_snippet_str_in_globals, format_str_in_globals};
+use rustc_span::ErrorGuaranteed;
+use std::error::Error;
+use std::fmt::{Display, Formatter as FmtFormatter};
+use std::path::Path;
+
+/// A formatting service that can be shared across threads.
+///
+/// Each call sets up the rustc session globals for the calling thread as needed, so callers do
+/// not need to know about them. Diagnostics for syntax errors are printed to stderr.
+pub struct Formatter {
+    config: Config,
+}
+
+/// The source could not be parsed
+#[derive(Clone, Copy, Debug)]
+pub struct ParseFailed;
+
+impl Display for ParseFailed {
+    fn fmt(&self, f: &mut FmtFormatter<'_>) -> std::fmt::Result {
+        f.write_str("failed to parse the source")
+    }
+}
+
+impl Error for ParseFailed {}
+
+impl Formatter {
+    pub fn new(config: Config) -> Self {
+        Formatter { config }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Formats the source of a module
+    pub fn format_str(&self, source: &str) -> Result<FormatModuleResult, ParseFailed> {
+        with_globals(|| format_str_in_globals(source, None, &self.config))
+    }
+
+    /// Formats the source of a module, naming it by the given path in errors. The file at the path
+    /// is not read.
+    pub fn format_str_with_path(
+        &self,
+        source: &str,
+        path: &Path,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        with_globals(|| format_str_in_globals(source, Some(path), &self.config))
+    }
+
+    /// Formats a standalone block expression at the given indentation
+    pub fn format_block_str(
+        &self,
+        source: &str,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        self.format_snippet_str(source, SnippetKind::Block, indent)
+    }
+
+    /// Formats a standalone expression at the given indentation
+    pub fn format_expr_str(
+        &self,
+        source: &str,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        self.format_snippet_str(source, SnippetKind::Expr, indent)
+    }
+
+    /// Formats a standalone item at the given indentation
+    pub fn format_item_str(
+        &self,
+        source: &str,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        self.format_snippet_str(source, SnippetKind::Item, indent)
+    }
+
+    /// Formats a standalone pattern at the given indentation
+    pub fn format_pat_str(
+        &self,
+        source: &str,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        self.format_snippet_str(source, SnippetKind::Pat, indent)
+    }
+
+    /// Formats a standalone type at the given indentation
+    pub fn format_ty_str(
+        &self,
+        source: &str,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        self.format_snippet_str(source, SnippetKind::Ty, indent)
+    }
+
+    fn format_snippet_str(
+        &self,
+        source: &str,
+        kind: SnippetKind,
+        indent: HSize,
+    ) -> Result<FormatModuleResult, ParseFailed> {
+        with_globals(|| format_snippet_str_in_globals(source, kind, &self.config, indent))
+    }
+}
+
+fn with_globals(
+    f: impl FnOnce() -> Result<FormatModuleResult, ErrorGuaranteed>,
+) -> Result<FormatModuleResult, ParseFailed> {
+    with_rustc_globals(f).map_err(|ErrorGuaranteed { .. }| ParseFailed)
+}
@@ -0,0 +1,46 @@
+use crate::config::GlobList;
+use crate::util::glob::glob_match;
+use rustc_lexer::{FrontmatterAllowed, TokenKind};
+use std::env;
+use std::path::{Component, Path};
+
+/// Returns the first glob in `ignore` that matches the given file. Paths under the current
+/// directory are matched relative to it.
+pub fn find_ignore_glob<'a>(path: &Path, ignore: &'a GlobList) -> Option<&'a str> {
+    if ignore.0.is_empty() {
+        return None;
+    }
+    let current_dir = env::current_dir().ok();
+    let path = current_dir
+        .as_deref()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+    let path = Vec::from_iter(path.components().filter_map(|component| match component {
+        Component::CurDir | Component::RootDir | Component::Prefix(_) => None,
+        _ => component.as_os_str().to_str(),
+    }))
+    .join("/");
+    ignore.0.iter().map(String::as_str).find(|glob| {
+        glob_match(glob, &path)
+    })
+}
+
+/// Returns true if the comments at the top of the file have a `@generated` or `DO NOT EDIT` marker
+pub fn is_generated_file(source: &str) -> bool {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let mut remaining = source;
+    for token in rustc_lexer::tokenize(source, FrontmatterAllowed::No) {
+        let (token_str, rest) = remaining.split_at(token.len as usize);
+        remaining = rest;
+        match token.kind {
+            TokenKind::BlockComment { .. } | TokenKind::LineComment { .. } => {
+                if token_str.contains("@generated") || token_str.contains("DO NOT EDIT") {
+                    return true;
+                }
+            }
+            TokenKind::Whitespace => {}
+            _ => break,
+        }
+    }
+    false
+}
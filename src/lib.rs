@@ -26,12 +26,14 @@
 // these crates are loaded from the sysroot, so they need extern crate.
 extern crate core;
 extern crate rustc_ast;
+extern crate rustc_ast_pretty;
 extern crate rustc_data_structures;
 extern crate rustc_driver;
 extern crate rustc_errors;
 extern crate rustc_expand;
 extern crate rustc_lexer;
 extern crate rustc_parse;
+extern crate rustc_parse_format;
 extern crate rustc_session;
 extern crate rustc_span;
 extern crate thin_vec;
@@ -39,16 +41,22 @@ extern crate thin_vec;
 mod ast_formatter;
 mod ast_module;
 mod ast_utils;
+pub mod cargo_workspace;
 pub mod config;
 mod constraint_writer;
 mod constraints;
 mod error;
 mod error_emitter;
+pub mod formatter;
+mod ignore;
 mod macro_args;
 mod module_extras;
+mod newline_style;
 mod num;
+pub mod options;
 mod parse;
 mod rustfmt_config_defaults;
+mod snippet;
 mod source_formatter;
 mod span;
 mod submodules;
@@ -59,23 +67,30 @@ thread_local! {
     pub static USE_TREE_ORDER_COUNT: Cell<u32> = Cell::new(0);
 }
 
-use crate::ast_formatter::format_module;
+use crate::ast_formatter::{format_module, format_snippet};
 use crate::config::Config;
-use crate::parse::{ParseModuleResult, parse_module};
+use crate::ignore::{find_ignore_glob, is_generated_file};
+use crate::newline_style::original_source;
+use crate::num::HSize;
+use crate::parse::{ParseModuleResult, ParseSnippetResult, parse_module, parse_snippet};
+use crate::snippet::SnippetKind;
 use crate::submodules::Submodule;
+use crate::util::atomic_write::write_atomic;
 use crate::util::rustc::init_rustc_globals;
+use rustc_ast::ast;
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust;
 use rustc_span::ErrorGuaranteed;
 use rustc_span::symbol::Ident;
 use std::cell::Cell;
 use std::collections::VecDeque;
 use std::error::Error;
 use std::fs;
-use std::io::Write;
+use std::io::{self, Write};
 use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::rc::Rc;
-use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct FormatModuleResult {
@@ -113,12 +128,14 @@ impl FormatModuleResult {
 pub enum CrateSource<'a> {
     File(&'a Path),
     Source(&'a str),
+    /// Source text for the file at the given path, which is not read from disk
+    SourceWithPath(&'a str, &'a Path),
 }
 
 impl<'a> CrateSource<'a> {
     pub fn path(self) -> Option<&'a Path> {
         match self {
-            CrateSource::File(path) => Some(path),
+            CrateSource::File(path) | CrateSource::SourceWithPath(_, path) => Some(path),
             CrateSource::Source(_) => None,
         }
     }
@@ -126,6 +143,7 @@ impl<'a> CrateSource<'a> {
 
 struct OnFormatModule {
     is_check: bool,
+    make_backup: bool,
     is_verbose: bool,
     has_errors: bool,
 }
@@ -151,15 +169,24 @@ impl OnFormatModule {
             if self.is_verbose {
                 eprintln!("Already formatted: {}", path.display());
             }
-        } else {
-            fs::write(path, formatted).unwrap();
-            if self.is_verbose {
-                eprintln!("Formatted: {}", path.display());
-            }
+        } else if let Err(e) = self.write_file(path, &formatted) {
+            eprintln!("Failed to write {}: {e}", path.display());
+            self.has_errors = true;
+        } else if self.is_verbose {
+            eprintln!("Formatted: {}", path.display());
         }
         ControlFlow::Continue(())
     }
 
+    fn write_file(&self, path: &Path, formatted: &str) -> io::Result<()> {
+        if self.make_backup {
+            let mut backup_path = path.as_os_str().to_owned();
+            backup_path.push(".bk");
+            fs::copy(path, backup_path)?;
+        }
+        write_atomic(path, formatted)
+    }
+
     fn check_file(&self, path: &Path, contents: &str, formatted: &str) -> ControlFlow<()> {
         if contents == formatted {
             if self.is_verbose {
@@ -186,7 +213,7 @@ impl OnFormatModule {
 }
 
 pub fn format_module_file_roots(
-    paths: Vec<String>,
+    paths: Vec<PathBuf>,
     config: Config,
     is_check: bool,
     is_verbose: bool,
@@ -194,15 +221,30 @@ pub fn format_module_file_roots(
     init_rustc_globals(|| {
         let config = Rc::new(config);
         let mut queue = VecDeque::<(PathBuf, Option<Ident>)>::from_iter(
-            paths.into_iter().map(|path| (path.into(), None)),
+            paths.into_iter().map(|path| (path, None)),
         );
         let mut on_format_module = OnFormatModule {
             is_check,
+            make_backup: config.make_backup,
             is_verbose,
             has_errors: false,
         };
         while let Some((path, relative)) = queue.pop_front() {
+            if let Some(glob) = find_ignore_glob(&path, &config.ignore) {
+                if is_verbose {
+                    eprintln!("Skipped: {} (matches ignore glob `{glob}`)", path.display());
+                }
+                continue;
+            }
             let submodules = format_module_file(&path, relative, &config, &mut on_format_module)?;
+            if config.skip_children {
+                if is_verbose {
+                    for submodule in &submodules {
+                        eprintln!("Skipped: {} (skip_children)", submodule.path.display());
+                    }
+                }
+                continue;
+            }
             queue.extend(
                 submodules
                     .into_iter()
@@ -222,16 +264,20 @@ fn format_module_file(
     config: &Config,
     on_format_module: &mut OnFormatModule,
 ) -> Result<Vec<Submodule>, ()> {
-    let result =
-        parse_module(CrateSource::File(path), relative).map_err(|ErrorGuaranteed { .. }| ())?;
+    let result = parse_module(CrateSource::File(path), relative, &config.macros)
+        .map_err(|ErrorGuaranteed { .. }| ())?;
     let ParseModuleResult {
         module,
         source_file,
         submodules,
     } = result;
-    let source = Arc::clone(source_file.src.as_ref().expect(
-        "the SourceFile should have src",
-    ));
+    let source = original_source(&source_file);
+    if !config.format_generated_files && is_generated_file(&source) {
+        if on_format_module.is_verbose {
+            eprintln!("Skipped: {} (generated file)", path.display());
+        }
+        return Ok(submodules);
+    }
     std::panic::set_hook({
         let path = path.to_path_buf();
         let prev_panic_hook = std::panic::take_hook();
@@ -254,14 +300,155 @@ fn format_module_file(
 }
 
 pub fn format_str(source: &str, config: Config) -> Result<FormatModuleResult, ErrorGuaranteed> {
-    init_rustc_globals(|| {
-        let ParseModuleResult {
-            module,
-            source_file,
-            submodules: _,
-        } = parse_module(CrateSource::Source(source), None)?;
-        Ok(format_module(Rc::new(module), source_file, None, &config))
-    })
+    init_rustc_globals(|| format_str_in_globals(source, None, &config))
+}
+
+/// Like `format_str`, but the source is named by the given path in errors, and it is returned
+/// unchanged if the path matches an ignore glob. The file at the path is not read.
+pub fn format_str_with_path(
+    source: &str,
+    path: &Path,
+    config: Config,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    init_rustc_globals(|| format_str_in_globals(source, Some(path), &config))
+}
+
+fn format_str_in_globals(
+    source: &str,
+    path: Option<&Path>,
+    config: &Config,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    let is_ignored = path.is_some_and(|path| find_ignore_glob(path, &config.ignore).is_some());
+    if is_ignored || !config.format_generated_files && is_generated_file(source) {
+        return Ok(FormatModuleResult {
+            error_count: 0,
+            formatted: source.to_owned(),
+        });
+    }
+    let crate_source = match path {
+        None => CrateSource::Source(source),
+        Some(path) => CrateSource::SourceWithPath(source, path),
+    };
+    let ParseModuleResult {
+        module,
+        source_file,
+        submodules: _,
+    } = parse_module(crate_source, None, &config.macros)?;
+    Ok(format_module(
+        Rc::new(module),
+        source_file,
+        path.map(Path::to_path_buf),
+        config,
+    ))
+}
+
+/// Formats a standalone block expression (e.g. `{ a; b }`) at the given indentation
+pub fn format_block_str(
+    source: &str,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_snippet_str(source, SnippetKind::Block, config, indent)
+}
+
+/// Formats a standalone expression at the given indentation
+pub fn format_expr_str(
+    source: &str,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_snippet_str(source, SnippetKind::Expr, config, indent)
+}
+
+/// Formats a standalone item at the given indentation
+pub fn format_item_str(
+    source: &str,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_snippet_str(source, SnippetKind::Item, config, indent)
+}
+
+/// Formats a standalone pattern at the given indentation
+pub fn format_pat_str(
+    source: &str,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_snippet_str(source, SnippetKind::Pat, config, indent)
+}
+
+/// Formats a standalone type at the given indentation
+pub fn format_ty_str(
+    source: &str,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_snippet_str(source, SnippetKind::Ty, config, indent)
+}
+
+fn format_snippet_str(
+    source: &str,
+    kind: SnippetKind,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    init_rustc_globals(|| format_snippet_str_in_globals(source, kind, &config, indent))
+}
+
+fn format_snippet_str_in_globals(
+    source: &str,
+    kind: SnippetKind,
+    config: &Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    let ParseSnippetResult {
+        snippet,
+        module,
+        source_file,
+    } = parse_snippet(source, kind, &config.macros, false)?;
+    Ok(format_snippet(&snippet, Rc::new(module), source_file, config, indent))
+}
+
+// The functions below format an AST or token stream that has no original source text, such as
+// one built by codegen. rustc's pretty printer produces the source text, which is then formatted
+// as if it were the original. These must be called within the rustc session globals that the
+// AST was created with, since symbols are interned there.
+
+/// Formats a crate that has no original source text
+pub fn format_ast_crate(
+    krate: &ast::Crate,
+    config: Config,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_str_in_globals(&pprust::crate_to_string_for_macros(krate), None, &config)
+}
+
+/// Formats an expression that has no original source text, at the given indentation
+pub fn format_ast_expr(
+    expr: &ast::Expr,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    let source = pprust::expr_to_string(expr);
+    format_snippet_str_in_globals(&source, SnippetKind::Expr, &config, indent)
+}
+
+/// Formats an item that has no original source text, at the given indentation
+pub fn format_ast_item(
+    item: &ast::Item,
+    config: Config,
+    indent: HSize,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    let source = pprust::item_to_string(item);
+    format_snippet_str_in_globals(&source, SnippetKind::Item, &config, indent)
+}
+
+/// Formats a token stream of items that has no original source text
+pub fn format_token_stream(
+    tokens: &TokenStream,
+    config: Config,
+) -> Result<FormatModuleResult, ErrorGuaranteed> {
+    format_str_in_globals(&pprust::tts_to_string(tokens), None, &config)
 }
 
 /// A flag to indicate that a returned error should trigger a fallback strategy
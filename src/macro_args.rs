@@ -1,7 +1,9 @@
-use crate::ast_formatter::std_macro::{StdMacro, std_macro};
-use crate::parse::parse_no_errors;
+use crate::ast_formatter::std_macro::{MacroOrigin, StdMacro, std_macro};
+use crate::config::MacroTable;
+use crate::parse::{parse_no_errors, silent_parse_sess};
 use rustc_ast::ast;
 use rustc_ast::token;
+use rustc_ast::token::Delimiter;
 use rustc_ast::visit::Visitor;
 use rustc_ast::visit::walk_list;
 use rustc_data_structures::fx::FxHashMap;
@@ -10,12 +12,18 @@ use rustc_parse::MACRO_ARGUMENTS;
 use rustc_parse::exp;
 use rustc_parse::parser::AttemptLocalParseRecovery;
 use rustc_parse::parser::CommaRecoveryMode;
+use rustc_parse::parser::FollowedByType;
+use rustc_parse::parser::ForceCollect;
 use rustc_parse::parser::Parser;
 use rustc_parse::parser::RecoverColon;
 use rustc_parse::parser::RecoverComma;
 use rustc_parse::parser::Recovery;
+use rustc_parse::parser::asm::{AsmArg, AsmArgKind, parse_asm_args};
+use rustc_parse::parser::attr::{AllowLeadingUnsafe, InnerAttrPolicy};
 use rustc_session::parse::ParseSess;
-use rustc_span::BytePos;
+use rustc_span::symbol::{Ident, kw};
+use rustc_span::{BytePos, Span, sym};
+use std::cell::OnceCell;
 use thin_vec::ThinVec;
 
 pub type MacroArgsMap = FxHashMap<BytePos, MacroArgs>;
@@ -26,24 +34,107 @@ pub fn mac_call_id(mac_call: &ast::MacCall) -> BytePos {
 
 pub struct MacroArgsParser<'a> {
     pub psess: &'a ParseSess,
+    pub macros: &'a MacroTable,
+    /// Used for unknown macros, whose arguments are often not valid Rust
+    pub silent_psess: OnceCell<ParseSess>,
     pub macro_args: MacroArgsMap,
 }
 
+impl MacroArgsParser<'_> {
+    fn silent_psess(&self) -> &ParseSess {
+        let psess = self
+            .silent_psess
+            .get_or_init(|| silent_parse_sess(self.psess));
+        // an error from a previous parse would fail the next one
+        psess.dcx().reset_err_count();
+        psess
+    }
+
+    fn visit_asm_arg(&mut self, arg: &AsmArg) {
+        let operand = match &arg.kind {
+            AsmArgKind::Template(expr) => return self.visit_expr(expr),
+            AsmArgKind::Operand(_, operand) => operand,
+            AsmArgKind::Options(_) | AsmArgKind::ClobberAbi(_) => return,
+        };
+        match operand {
+            ast::InlineAsmOperand::In { expr, .. } | ast::InlineAsmOperand::InOut { expr, .. } => {
+                self.visit_expr(expr)
+            }
+            ast::InlineAsmOperand::Out { expr, .. } => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+            }
+            ast::InlineAsmOperand::SplitInOut {
+                in_expr,
+                out_expr,
+                ..
+            } => {
+                self.visit_expr(in_expr);
+                if let Some(out_expr) = out_expr {
+                    self.visit_expr(out_expr);
+                }
+            }
+            ast::InlineAsmOperand::Const { anon_const } => self.visit_anon_const(anon_const),
+            ast::InlineAsmOperand::Sym { sym } => self.visit_inline_asm_sym(sym),
+            ast::InlineAsmOperand::Label { block } => self.visit_block(block),
+        }
+    }
+}
+
 impl Visitor<'_> for MacroArgsParser<'_> {
     fn visit_mac_call(&mut self, mac_call: &ast::MacCall) {
-        if let Some(mac_args) = try_parse_macro_args(self.psess, mac_call) {
+        let mac_args = match std_macro(mac_call, self.macros) {
+            Some((std_macro, MacroOrigin::Std)) => {
+                try_parse_macro_args(self.psess, mac_call, std_macro)
+            }
+            // a configured or crate macro may accept arguments that do not fit its kind
+            Some((std_macro, MacroOrigin::Configured | MacroOrigin::Crate)) => {
+                try_parse_macro_args(self.silent_psess(), mac_call, std_macro)
+            }
+            None => try_parse_unknown_macro_args(self.silent_psess(), mac_call),
+        };
+        if let Some(mac_args) = mac_args {
             // recursively walk parsed macro args for nested macro calls
             match &mac_args {
+                MacroArgs::Asm(args) => {
+                    for arg in args {
+                        self.visit_asm_arg(arg);
+                    }
+                }
+                MacroArgs::Bitflags(bitflags) => {
+                    for bitflags in bitflags {
+                        self.visit_ty(&bitflags.ty);
+                        for flag in &bitflags.flags {
+                            self.visit_expr(&flag.expr);
+                        }
+                    }
+                }
                 MacroArgs::Cfg(_) => {}
+                MacroArgs::CfgIf(branches) => {
+                    for branch in branches {
+                        walk_list!(self, visit_item, &branch.items);
+                    }
+                }
                 MacroArgs::FnLike(args) | MacroArgs::Format { args, .. } => {
                     walk_list!(self, visit_expr, args);
                 }
-                MacroArgs::Matches(expr, pat, guard) => {
+                MacroArgs::Items(items) => {
+                    walk_list!(self, visit_item, items);
+                }
+                MacroArgs::LazyStatic(statics) => {
+                    for lazy_static in statics {
+                        self.visit_ty(&lazy_static.ty);
+                        self.visit_expr(&lazy_static.expr);
+                    }
+                }
+                MacroArgs::Matches(expr, pat, guard, args) => {
                     self.visit_expr(expr);
                     self.visit_pat(pat);
                     if let Some(guard) = guard {
                         self.visit_expr(guard);
                     }
+                    walk_list!(self, visit_expr, args);
                 }
                 MacroArgs::ThreadLocal(stmts) => {
                     walk_list!(self, visit_stmt, stmts);
@@ -55,25 +146,77 @@ impl Visitor<'_> for MacroArgsParser<'_> {
 }
 
 pub enum MacroArgs {
+    Asm(Vec<AsmArg>),
+    Bitflags(ThinVec<Bitflags>),
     Cfg(ThinVec<ast::MetaItemInner>),
+    CfgIf(ThinVec<CfgIfBranch>),
     /// Same as a function call. Optional trailing comma. Also used for macros with no args.
     FnLike(ThinVec<Box<ast::Expr>>),
     Format {
         args: ThinVec<Box<ast::Expr>>,
         format_string_pos: u8,
+        /// The first argument is preceded by `target:`, as in the `log` crate
+        has_target: bool,
     },
-    Matches(Box<ast::Expr>, Box<ast::Pat>, Option<Box<ast::Expr>>),
+    Items(ThinVec<Box<ast::Item>>),
+    LazyStatic(ThinVec<LazyStatic>),
+    /// `matches!` or `assert_matches!`, with the optional format arguments of the latter
+    Matches(Box<ast::Expr>, Box<ast::Pat>, Option<Box<ast::Expr>>, ThinVec<Box<ast::Expr>>),
     ThreadLocal(ThinVec<ast::Stmt>),
 }
 
+/// `[pub] struct Name: Ty { const FLAG = expr; .. }` in `bitflags!`, or `impl Name: Ty { .. }`
+pub struct Bitflags {
+    pub attrs: ast::AttrVec,
+    pub vis: ast::Visibility,
+    pub is_impl: bool,
+    pub ident: Ident,
+    pub ty: Box<ast::Ty>,
+    pub flags: ThinVec<BitflagsFlag>,
+    pub span: Span,
+}
+
+pub struct BitflagsFlag {
+    pub attrs: ast::AttrVec,
+    /// May be `_`
+    pub ident: Ident,
+    pub expr: Box<ast::Expr>,
+    pub span: Span,
+}
+
+/// One of `if #[cfg(..)] { .. }`, `else if #[cfg(..)] { .. }` or `else { .. }` in `cfg_if!`
+pub struct CfgIfBranch {
+    pub cfg: Option<ast::MetaItem>,
+    pub items: ThinVec<Box<ast::Item>>,
+}
+
+/// `[pub] static ref NAME: Ty = expr;` in `lazy_static!`
+pub struct LazyStatic {
+    pub attrs: ast::AttrVec,
+    pub vis: ast::Visibility,
+    pub ident: Ident,
+    pub ty: Box<ast::Ty>,
+    pub expr: Box<ast::Expr>,
+    pub span: Span,
+}
+
 // todo emit an error if we fail to parse a known macro? at least in debug mode
-pub fn try_parse_macro_args(psess: &ParseSess, mac_call: &ast::MacCall) -> Option<MacroArgs> {
-    let std_macro = std_macro(mac_call)?;
+pub fn try_parse_macro_args(
+    psess: &ParseSess,
+    mac_call: &ast::MacCall,
+    std_macro: StdMacro,
+) -> Option<MacroArgs> {
+    if std_macro == StdMacro::Verbatim {
+        return None;
+    }
     // todo is MACRO_ARGUMENTS necessary?
     // todo silence errors except in debug mode
     let parser = Parser::new(psess, mac_call.args.tokens.clone(), MACRO_ARGUMENTS)
         .recovery(Recovery::Forbidden);
     let macro_args = parse_no_errors(parser, |parser| match std_macro {
+        StdMacro::Asm(asm_macro) => {
+            parse_asm_args(parser, mac_call.args.dspan.entire(), asm_macro).map(MacroArgs::Asm)
+        }
         StdMacro::Cfg => {
             parse_comma_sep_list(parser, Parser::parse_meta_item_inner).map(MacroArgs::Cfg)
         }
@@ -81,20 +224,130 @@ pub fn try_parse_macro_args(psess: &ParseSess, mac_call: &ast::MacCall) -> Optio
             parse_comma_sep_list(parser, Parser::parse_expr).map(MacroArgs::FnLike)
         }
         StdMacro::Format { format_string_pos } => {
+            let has_target = parser.token.is_ident_named(sym::target)
+                && parser.look_ahead(1, |token| *token == token::Colon);
+            if has_target {
+                parser.bump();
+                parser.bump();
+            }
             parse_comma_sep_list(parser, Parser::parse_expr).map(|args| {
                 MacroArgs::Format {
                     args,
                     format_string_pos,
+                    has_target,
                 }
             })
         }
-        StdMacro::Matches => parse_matches(parser),
+        StdMacro::Items => parse_items(parser),
+        StdMacro::AssertMatches => parse_matches(parser, true),
+        StdMacro::Bitflags => parse_bitflags(parser),
+        StdMacro::CfgIf => parse_cfg_if(parser),
+        StdMacro::LazyStatic => parse_lazy_static(parser),
+        StdMacro::Matches => parse_matches(parser, false),
         StdMacro::ThreadLocal => parse_thread_local(parser),
+        StdMacro::Verbatim => unreachable!(),
     })
     .ok()?;
     Some(macro_args)
 }
 
+/// Parses the arguments of an unknown macro like the arguments of a function call. The formatted
+/// arguments are only used if they have the same token stream as the source.
+fn try_parse_unknown_macro_args(psess: &ParseSess, mac_call: &ast::MacCall) -> Option<MacroArgs> {
+    if !matches!(
+        mac_call.args.delim,
+        Delimiter::Parenthesis | Delimiter::Bracket,
+    ) {
+        return None;
+    }
+    let parser = Parser::new(psess, mac_call.args.tokens.clone(), MACRO_ARGUMENTS)
+        .recovery(Recovery::Forbidden);
+    let args = parse_no_errors(parser, |parser| {
+        parse_comma_sep_list(parser, Parser::parse_expr)
+    })
+    .ok()?;
+    Some(MacroArgs::FnLike(args))
+}
+
+fn parse_bitflags<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
+    let mut bitflags = ThinVec::new();
+    while !parser.eat(exp!(Eof)) {
+        let lo = parser.token.span;
+        let attrs = parse_outer_attrs(parser)?;
+        let vis = parser.parse_visibility(FollowedByType::No)?;
+        let is_impl = parser.eat_keyword(exp!(Impl));
+        if !is_impl {
+            parser.expect_keyword(exp!(Struct))?;
+        }
+        let ident = parser.parse_ident()?;
+        parser.expect(exp!(Colon))?;
+        let ty = parser.parse_ty()?;
+        parser.expect(exp!(OpenBrace))?;
+        let mut flags = ThinVec::new();
+        while !parser.eat(exp!(CloseBrace)) {
+            let lo = parser.token.span;
+            let attrs = parse_outer_attrs(parser)?;
+            parser.expect_keyword(exp!(Const))?;
+            let ident = if parser.eat_keyword(exp!(Underscore)) {
+                Ident::new(kw::Underscore, parser.prev_token.span)
+            } else {
+                parser.parse_ident()?
+            };
+            parser.expect(exp!(Eq))?;
+            let expr = parser.parse_expr()?;
+            parser.expect(exp!(Semi))?;
+            flags.push(BitflagsFlag {
+                attrs,
+                ident,
+                expr,
+                span: lo.to(parser.prev_token.span),
+            });
+        }
+        bitflags.push(Bitflags {
+            attrs,
+            vis,
+            is_impl,
+            ident,
+            ty,
+            flags,
+            span: lo.to(parser.prev_token.span),
+        });
+    }
+    Ok(MacroArgs::Bitflags(bitflags))
+}
+
+fn parse_cfg_if<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
+    let mut branches = ThinVec::new();
+    loop {
+        let cfg = if parser.eat_keyword(exp!(If)) {
+            parser.expect(exp!(Pound))?;
+            parser.expect(exp!(OpenBracket))?;
+            let meta = parser.parse_meta_item(AllowLeadingUnsafe::No)?;
+            parser.expect(exp!(CloseBracket))?;
+            Some(meta)
+        } else if branches.is_empty() {
+            return parser.unexpected_any();
+        } else {
+            None
+        };
+        let is_else = cfg.is_none();
+        parser.expect(exp!(OpenBrace))?;
+        let mut items = ThinVec::new();
+        while !parser.eat(exp!(CloseBrace)) {
+            match parser.parse_item(ForceCollect::No)? {
+                Some(item) => items.push(item),
+                None => return parser.unexpected_any(),
+            }
+        }
+        branches.push(CfgIfBranch { cfg, items });
+        if is_else || !parser.eat_keyword(exp!(Else)) {
+            break;
+        }
+    }
+    parser.expect(exp!(Eof))?;
+    Ok(MacroArgs::CfgIf(branches))
+}
+
 fn parse_comma_sep_list<'p, T>(
     parser: &mut Parser<'p>,
     parse: fn(&mut Parser<'p>) -> PResult<'p, T>,
@@ -114,7 +367,33 @@ fn parse_comma_sep_list<'p, T>(
     Ok(list)
 }
 
-fn parse_matches<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
+fn parse_lazy_static<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
+    let mut statics = ThinVec::new();
+    while !parser.eat(exp!(Eof)) {
+        let lo = parser.token.span;
+        let attrs = parse_outer_attrs(parser)?;
+        let vis = parser.parse_visibility(FollowedByType::No)?;
+        parser.expect_keyword(exp!(Static))?;
+        parser.expect_keyword(exp!(Ref))?;
+        let ident = parser.parse_ident()?;
+        parser.expect(exp!(Colon))?;
+        let ty = parser.parse_ty()?;
+        parser.expect(exp!(Eq))?;
+        let expr = parser.parse_expr()?;
+        parser.expect(exp!(Semi))?;
+        statics.push(LazyStatic {
+            attrs,
+            vis,
+            ident,
+            ty,
+            expr,
+            span: lo.to(parser.prev_token.span),
+        });
+    }
+    Ok(MacroArgs::LazyStatic(statics))
+}
+
+fn parse_matches<'p>(parser: &mut Parser<'p>, has_format_args: bool) -> PResult<'p, MacroArgs> {
     let expr = parser.parse_expr()?;
     parser.expect(exp!(Comma))?;
     let pat = parser.parse_pat_no_top_guard(
@@ -128,9 +407,39 @@ fn parse_matches<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
     } else {
         None
     };
-    let _ = parser.eat(exp!(Comma));
-    parser.expect(exp!(Eof))?;
-    Ok(MacroArgs::Matches(expr, pat, guard))
+    let args = if parser.eat(exp!(Comma)) && has_format_args {
+        parse_comma_sep_list(parser, Parser::parse_expr)?
+    } else {
+        parser.expect(exp!(Eof))?;
+        ThinVec::new()
+    };
+    Ok(MacroArgs::Matches(expr, pat, guard, args))
+}
+
+fn parse_items<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
+    let mut items = ThinVec::new();
+    while !parser.eat(exp!(Eof)) {
+        match parser.parse_item(ForceCollect::No)? {
+            Some(item) => items.push(item),
+            None => return parser.unexpected_any(),
+        }
+    }
+    Ok(MacroArgs::Items(items))
+}
+
+/// Parses outer attributes. Doc comments are skipped since they are formatted as comments.
+fn parse_outer_attrs<'p>(parser: &mut Parser<'p>) -> PResult<'p, ast::AttrVec> {
+    let mut attrs = ast::AttrVec::new();
+    loop {
+        if parser.token == token::Pound {
+            attrs.push(parser.parse_attribute(InnerAttrPolicy::Forbidden(None))?);
+        } else if matches!(parser.token.kind, token::DocComment(..)) {
+            parser.bump();
+        } else {
+            break;
+        }
+    }
+    Ok(attrs)
 }
 
 fn parse_thread_local<'p>(parser: &mut Parser<'p>) -> PResult<'p, MacroArgs> {
@@ -3,10 +3,16 @@
 extern crate rustc_span;
 
 use getopts::Options;
-use myrustfmt::config::Config;
-use myrustfmt::{FormatModuleResult, USE_TREE_ORDER_COUNT, format_module_file_roots, format_str};
+use myrustfmt::cargo_workspace::find_package_edition;
+use myrustfmt::config::{Config, Edition};
+use myrustfmt::options::{add_shared_options, config_from_matches};
+use myrustfmt::{
+    FormatModuleResult, USE_TREE_ORDER_COUNT, format_module_file_roots, format_str,
+    format_str_with_path,
+};
 use rustc_span::ErrorGuaranteed;
 use std::io::{Write, stdin, stdout};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::{env, io};
 
@@ -18,28 +24,29 @@ fn main() -> ExitCode {
     }
     let options = build_options();
     let options_matches = options.parse(&args[1..]).unwrap();
-    if options_matches.opt_present("edition") {
-        // todo wat
-        eprintln!("WARNING: Ignoring --edition option");
-    }
     let is_check = options_matches.opt_present("check");
     let is_verbose = options_matches.opt_present("verbose");
-    let mut config = Config::default();
-    if let Some(max_width) = options_matches.opt_str("max-width") {
-        config.max_width = match max_width.parse() {
-            Ok(val) => val,
-            Err(_) => {
-                eprintln!("Invalid max-width value");
-                return ExitCode::FAILURE;
-            }
-        };
-    }
+    let config = match config_from_matches(&options_matches) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
     // todo dedupe files and their submodules (two files can have a shared submodule, like in tests/)
-    let paths = options_matches.free;
-    if let [path] = &paths[..]
-        && path == "-"
-    {
-        return do_stdin(config);
+    let stdin_filepath = options_matches.opt_str("stdin-filepath").map(PathBuf::from);
+    let paths = Vec::from_iter(options_matches.free.iter().map(PathBuf::from));
+    let is_stdin = match &paths[..] {
+        [] => stdin_filepath.is_some(),
+        [path] => path == Path::new("-"),
+        _ => false,
+    };
+    if is_stdin {
+        return do_stdin(config, stdin_filepath.as_deref());
+    }
+    if stdin_filepath.is_some() {
+        eprintln!("--stdin-filepath can only be used when formatting stdin");
+        return ExitCode::FAILURE;
     }
     let exit_code = match format_module_file_roots(paths, config, is_check, is_verbose) {
         Ok(()) => ExitCode::SUCCESS,
@@ -49,9 +56,24 @@ fn main() -> ExitCode {
     exit_code
 }
 
-fn do_stdin(config: Config) -> ExitCode {
+fn do_stdin(mut config: Config, path: Option<&Path>) -> ExitCode {
+    if let Some(path) = path
+        && config.edition == Edition::Unknown
+    {
+        match find_package_edition(path) {
+            Ok(edition) => config.edition = edition.unwrap_or(Edition::Unknown),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
     let input = io::read_to_string(stdin()).expect("failed to read stdin");
-    match format_str(&input, config) {
+    let result = match path {
+        None => format_str(&input, config),
+        Some(path) => format_str_with_path(&input, path, config),
+    };
+    match result {
         Ok(
             FormatModuleResult {
                 error_count,
@@ -74,19 +96,13 @@ fn do_stdin(config: Config) -> ExitCode {
 
 fn build_options() -> Options {
     let mut opts = Options::new();
-    opts.optflag(
-        "",
-        "check",
-        "Run in 'check' mode. Exits with 0 if input is formatted correctly. Exits \
-         with 1 and prints a diff if formatting is required.",
-    );
+    add_shared_options(&mut opts);
     opts.optopt(
         "",
-        "edition",
-        "Rust edition to use",
-        "[2015|2018|2021|2024]",
+        "stdin-filepath",
+        "Path of the file being formatted from stdin. The file is not read, but the path is \
+         used to name it in errors.",
+        "PATH",
     );
-    opts.optopt("", "max-width", "Maximum width of each line", "WIDTH");
-    opts.optflag("v", "verbose", "Print verbose output");
     opts
 }
@@ -1,7 +1,9 @@
 use crate::Submodule;
 use crate::ast_utils::use_tree_order::{SortedUseTreeMap, use_tree_order};
+use crate::config::MacroTable;
 use crate::macro_args::MacroArgsMap;
 use crate::macro_args::MacroArgsParser;
+use crate::snippet::Snippet;
 use crate::submodules::SubmoduleCollector;
 use rustc_ast::ast;
 use rustc_ast::visit;
@@ -9,6 +11,7 @@ use rustc_ast::visit::Visitor;
 use rustc_data_structures::fx::FxHashMap;
 use rustc_session::parse::ParseSess;
 use rustc_span::Ident;
+use std::cell::OnceCell;
 use std::path::Path;
 
 pub struct ModuleExtras {
@@ -21,6 +24,7 @@ pub struct ModuleExtras {
 ///   is ./foo/
 pub fn get_module_extras(
     psess: &ParseSess,
+    macros: &MacroTable,
     items: &[Box<ast::Item>],
     path: Option<&Path>,
     relative: Option<Ident>,
@@ -36,23 +40,27 @@ pub fn get_module_extras(
             submodules: Vec::new(),
         }
     });
-    let mut visitor = ModuleExtrasVisitor {
-        psess,
-        macro_args: MacroArgsParser {
-            psess,
-            macro_args: Default::default(),
-        },
-        sorted_use_trees: FxHashMap::default(),
-        submodules,
-    };
+    let mut visitor = ModuleExtrasVisitor::new(psess, macros, submodules);
     for item in items {
         visitor.visit_item(item);
     }
-    ModuleExtras {
-        macro_args: visitor.macro_args.macro_args,
-        sorted_use_trees: visitor.sorted_use_trees,
-        submodules: visitor.submodules.map_or_default(|s| s.submodules),
+    visitor.finish()
+}
+
+pub fn get_snippet_extras(
+    psess: &ParseSess,
+    macros: &MacroTable,
+    snippet: &Snippet,
+) -> ModuleExtras {
+    let mut visitor = ModuleExtrasVisitor::new(psess, macros, None);
+    match snippet {
+        Snippet::Block(block) => visitor.visit_block(block),
+        Snippet::Expr(expr) => visitor.visit_expr(expr),
+        Snippet::Item(item) => visitor.visit_item(item),
+        Snippet::Pat(pat) => visitor.visit_pat(pat),
+        Snippet::Ty(ty) => visitor.visit_ty(ty),
     }
+    visitor.finish()
 }
 
 struct ModuleExtrasVisitor<'psess> {
@@ -62,6 +70,34 @@ struct ModuleExtrasVisitor<'psess> {
     submodules: Option<SubmoduleCollector>,
 }
 
+impl<'psess> ModuleExtrasVisitor<'psess> {
+    fn new(
+        psess: &'psess ParseSess,
+        macros: &'psess MacroTable,
+        submodules: Option<SubmoduleCollector>,
+    ) -> ModuleExtrasVisitor<'psess> {
+        ModuleExtrasVisitor {
+            psess,
+            macro_args: MacroArgsParser {
+                psess,
+                macros,
+                silent_psess: OnceCell::new(),
+                macro_args: Default::default(),
+            },
+            sorted_use_trees: FxHashMap::default(),
+            submodules,
+        }
+    }
+
+    fn finish(self) -> ModuleExtras {
+        ModuleExtras {
+            macro_args: self.macro_args.macro_args,
+            sorted_use_trees: self.sorted_use_trees,
+            submodules: self.submodules.map_or_default(|s| s.submodules),
+        }
+    }
+}
+
 impl Visitor<'_> for ModuleExtrasVisitor<'_> {
     fn visit_item(&mut self, item: &ast::Item) {
         if let Some(submodules) = &mut self.submodules {
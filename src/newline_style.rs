@@ -0,0 +1,52 @@
+//! rustc removes a byte order mark and converts CRLF line endings to LF when it loads a source
+//! file, so formatting is done with LF line endings. The original line endings are restored in
+//! the output according to the `newline_style` config.
+
+use crate::config::NewlineStyle;
+use rustc_span::SourceFile;
+
+const BOM: char = '\u{feff}';
+
+/// Returns the source text as it was before rustc normalized it
+pub fn original_source(source_file: &SourceFile) -> String {
+    let src = source_file.src.as_ref().expect(
+        "the SourceFile should have src",
+    );
+    let mut original = String::with_capacity(src.len() + source_file.normalized_pos.len());
+    let mut pos = 0;
+    for normalized_pos in &source_file.normalized_pos {
+        let normalized_pos = normalized_pos.pos.0 as usize;
+        if normalized_pos == 0 {
+            original.push(BOM);
+            continue;
+        }
+        // a `\r` was removed before the `\n` that precedes this position
+        let newline_pos = normalized_pos - 1;
+        original.push_str(&src[pos..newline_pos]);
+        original.push('\r');
+        pos = newline_pos;
+    }
+    original.push_str(&src[pos..]);
+    original
+}
+
+/// Converts formatted output with LF line endings to the configured newline style, and adds a
+/// byte order mark if the original source has one
+pub fn apply_newline_style(style: NewlineStyle, formatted: &mut String, original: &str) {
+    let is_windows = match style {
+        NewlineStyle::Auto => {
+            original
+                .find('\n')
+                .is_some_and(|i| original[..i].ends_with('\r'))
+        }
+        NewlineStyle::Native => cfg!(windows),
+        NewlineStyle::Unix => false,
+        NewlineStyle::Windows => true,
+    };
+    if is_windows {
+        *formatted = formatted.replace('\n', "\r\n");
+    }
+    if original.starts_with(BOM) {
+        formatted.insert(0, BOM);
+    }
+}
@@ -0,0 +1,141 @@
+//! Command line options shared by `myrustfmt` and `cargo myrustfmt`
+
+use crate::config::Config;
+use getopts::{Matches, Options};
+
+/// Adds the options that are common to both binaries
+pub fn add_shared_options(opts: &mut Options) {
+    opts.optflag(
+        "",
+        "backup",
+        "Keep a copy of each formatted file, named like main.rs.bk",
+    );
+    opts.optflag(
+        "",
+        "check",
+        "Run in 'check' mode. Exits with 0 if input is formatted correctly. Exits \
+         with 1 and prints a diff if formatting is required.",
+    );
+    opts.optopt(
+        "",
+        "edition",
+        "Rust edition to use. By default, cargo myrustfmt and --stdin-filepath read it from \
+         Cargo.toml.",
+        "[2015|2018|2021|2024]",
+    );
+    opts.optflag(
+        "",
+        "format-generated-files",
+        "Format files marked with @generated or DO NOT EDIT",
+    );
+    opts.optmulti(
+        "",
+        "ignore",
+        "Do not format files matching the glob",
+        "GLOB",
+    );
+    opts.optflag(
+        "",
+        "inline-format-args",
+        "Inline identifier arguments of format macros into the format string",
+    );
+    opts.optopt(
+        "",
+        "line-comment-not-allowed",
+        "How to report a line comment that must be on one line",
+        "[allow|warn|error]",
+    );
+    opts.optopt(
+        "",
+        "macros",
+        "How to format macros by name, as a TOML table like \
+         'info = { kind = \"format\" }'. Kinds are fn_like, format, items and vec.",
+        "TABLE",
+    );
+    opts.optopt("", "max-width", "Maximum width of each line", "WIDTH");
+    opts.optopt(
+        "",
+        "max-width-exceeded",
+        "How to report a line that exceeds the max width",
+        "[allow|warn|error]",
+    );
+    opts.optopt(
+        "",
+        "multi-line-comment-not-allowed",
+        "How to report a multi-line comment that must be on one line",
+        "[allow|warn|error]",
+    );
+    opts.optopt(
+        "",
+        "newline-style",
+        "Line endings of formatted files",
+        "[Auto|Native|Unix|Windows]",
+    );
+    opts.optflag(
+        "",
+        "no-error-on-line-overflow",
+        "Do not report lines that exceed the max width",
+    );
+    opts.optflag(
+        "",
+        "skip-children",
+        "Do not format submodules found in other files",
+    );
+    opts.optopt(
+        "",
+        "unsupported-syntax",
+        "How to report syntax that is copied from source because it cannot be formatted",
+        "[allow|warn|error]",
+    );
+    opts.optflag("v", "verbose", "Print verbose output");
+}
+
+/// Builds the `Config` from the options added by `add_shared_options`
+pub fn config_from_matches(matches: &Matches) -> Result<Config, String> {
+    let mut config = Config::default();
+    if matches.opt_present("backup") {
+        config.make_backup = true;
+    }
+    if let Some(edition) = matches.opt_str("edition") {
+        config.edition = edition.parse()?;
+    }
+    if matches.opt_present("format-generated-files") {
+        config.format_generated_files = true;
+    }
+    for glob in matches.opt_strs("ignore") {
+        config.ignore.0.push(glob);
+    }
+    if matches.opt_present("inline-format-args") {
+        config.inline_format_args = true;
+    }
+    if let Some(severity) = matches.opt_str("line-comment-not-allowed") {
+        config.line_comment_not_allowed = severity.parse()?;
+    }
+    if let Some(macros) = matches.opt_str("macros") {
+        config.macros = macros.parse()?;
+    }
+    if let Some(severity) = matches.opt_str("max-width-exceeded") {
+        config.max_width_exceeded = severity.parse()?;
+    }
+    if let Some(severity) = matches.opt_str("multi-line-comment-not-allowed") {
+        config.multi_line_comment_not_allowed = severity.parse()?;
+    }
+    if let Some(newline_style) = matches.opt_str("newline-style") {
+        config.newline_style = newline_style.parse()?;
+    }
+    if matches.opt_present("no-error-on-line-overflow") {
+        config.error_on_line_overflow = false;
+    }
+    if matches.opt_present("skip-children") {
+        config.skip_children = true;
+    }
+    if let Some(severity) = matches.opt_str("unsupported-syntax") {
+        config.unsupported_syntax = severity.parse()?;
+    }
+    if let Some(max_width) = matches.opt_str("max-width") {
+        config.max_width = max_width.parse().map_err(|_| {
+            "Invalid max-width value".to_owned()
+        })?;
+    }
+    Ok(config)
+}
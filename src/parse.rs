@@ -1,21 +1,30 @@
 use crate::CrateSource;
 use crate::ast_module::AstModule;
-use crate::module_extras::{ModuleExtras, get_module_extras};
+use crate::config::MacroTable;
+use crate::module_extras::{ModuleExtras, get_module_extras, get_snippet_extras};
+use crate::snippet::{Snippet, SnippetKind};
 use crate::submodules::Submodule;
+use rustc_ast::ast;
 use rustc_errors::ColorConfig;
 use rustc_errors::DiagCtxt;
 use rustc_errors::ErrorGuaranteed;
 use rustc_errors::PResult;
 use rustc_errors::emitter::HumanEmitter;
 use rustc_errors::emitter::stderr_destination;
+use rustc_parse::exp;
 use rustc_parse::lexer::StripTokens;
+use rustc_parse::parser::CommaRecoveryMode;
 use rustc_parse::parser::ExpTokenPair;
+use rustc_parse::parser::ForceCollect;
 use rustc_parse::parser::Parser;
+use rustc_parse::parser::RecoverColon;
+use rustc_parse::parser::RecoverComma;
 use rustc_session::parse::ParseSess;
 use rustc_span::source_map::{FilePathMapping, SourceMap};
 use rustc_span::symbol::Ident;
 use rustc_span::{FileName, SourceFile};
 use std::sync::Arc;
+use thin_vec::ThinVec;
 
 pub struct ParseModuleResult {
     pub module: AstModule,
@@ -23,22 +32,18 @@ pub struct ParseModuleResult {
     pub submodules: Vec<Submodule>,
 }
 
+pub struct ParseSnippetResult {
+    pub snippet: Snippet,
+    pub module: AstModule,
+    pub source_file: SourceFile,
+}
+
 pub fn parse_module(
     crate_source: CrateSource,
     relative: Option<Ident>,
+    macros: &MacroTable,
 ) -> Result<ParseModuleResult, ErrorGuaranteed> {
-    let module;
-    let submodules;
-    let source_file;
-    // This block ensures we have a unique reference to the SourceFile at the end.
-    {
-        // Create a fresh SourceMap, ParseSess, etc. for every file to avoid unnecessarily
-        // accumulating files in memory.
-        let source_map = Arc::new(SourceMap::new(FilePathMapping::empty()));
-        let dcx = build_diag_ctxt(Arc::clone(&source_map));
-        let psess = ParseSess::with_dcx(dcx, Arc::clone(&source_map));
-
-        let parser = module_parser(&psess, crate_source);
+    let ((module, submodules), source_file) = parse_source(crate_source, false, |psess, parser| {
         let (attrs, items, spans) = parse_no_errors(parser, |parser| {
             parser.parse_mod(ExpTokenPair {
                 tok: rustc_ast::token::Eof,
@@ -46,25 +51,110 @@ pub fn parse_module(
             })
         })?;
 
-        let macro_args;
-        let sorted_use_trees;
-        ModuleExtras {
+        let ModuleExtras {
             macro_args,
             sorted_use_trees,
             submodules,
-        } = get_module_extras(&psess, &items, crate_source.path(), relative);
+        } = get_module_extras(psess, macros, &items, crate_source.path(), relative);
 
-        if let Some(e) = psess.dcx().has_errors() {
-            return Err(e);
-        }
-
-        module = AstModule {
+        let module = AstModule {
             attrs,
             items,
             macro_args,
             sorted_use_trees,
             spans,
         };
+        Ok((module, submodules))
+    })?;
+
+    Ok(ParseModuleResult {
+        module,
+        source_file,
+        submodules,
+    })
+}
+
+/// Parses a snippet of the given kind. If `is_silent` is true, parse errors are not printed.
+pub fn parse_snippet(
+    source: &str,
+    kind: SnippetKind,
+    macros: &MacroTable,
+    is_silent: bool,
+) -> Result<ParseSnippetResult, ErrorGuaranteed> {
+    let ((snippet, module), source_file) = parse_source(
+        CrateSource::Source(source),
+        is_silent,
+        |psess, parser| {
+            let snippet = parse_no_errors(parser, |parser| {
+                let snippet = match kind {
+                    SnippetKind::Block => Snippet::Block(parser.parse_block()?),
+                    SnippetKind::Expr => Snippet::Expr(parser.parse_expr()?),
+                    SnippetKind::Item => match parser.parse_item(ForceCollect::No)? {
+                        Some(item) => Snippet::Item(item),
+                        None => parser.unexpected_any()?,
+                    },
+                    SnippetKind::Pat => Snippet::Pat(parser.parse_pat_no_top_guard(
+                        None,
+                        RecoverComma::No,
+                        RecoverColon::No,
+                        CommaRecoveryMode::EitherTupleOrPipe,
+                    )?),
+                    SnippetKind::Ty => Snippet::Ty(parser.parse_ty()?),
+                };
+                parser.expect(exp!(Eof))?;
+                Ok(snippet)
+            })?;
+
+            let ModuleExtras {
+                macro_args,
+                sorted_use_trees,
+                submodules: _,
+            } = get_snippet_extras(psess, macros, &snippet);
+
+            let module = AstModule {
+                attrs: ThinVec::new(),
+                items: ThinVec::new(),
+                macro_args,
+                sorted_use_trees,
+                spans: ast::ModSpans::default(),
+            };
+            Ok((snippet, module))
+        },
+    )?;
+
+    Ok(ParseSnippetResult {
+        snippet,
+        module,
+        source_file,
+    })
+}
+
+/// Parses a single source file with a fresh `ParseSess`, and returns the `SourceFile` along with
+/// the parsed output.
+fn parse_source<T>(
+    crate_source: CrateSource,
+    is_silent: bool,
+    parse: impl FnOnce(&ParseSess, Parser<'_>) -> Result<T, ErrorGuaranteed>,
+) -> Result<(T, SourceFile), ErrorGuaranteed> {
+    let output;
+    let source_file;
+    // This block ensures we have a unique reference to the SourceFile at the end.
+    {
+        // Create a fresh SourceMap, ParseSess, etc. for every file to avoid unnecessarily
+        // accumulating files in memory.
+        let source_map = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let dcx = build_diag_ctxt(Arc::clone(&source_map));
+        let psess = ParseSess::with_dcx(dcx, Arc::clone(&source_map));
+        if is_silent {
+            psess.dcx().make_silent();
+        }
+
+        let parser = module_parser(&psess, crate_source);
+        output = parse(&psess, parser)?;
+
+        if let Some(e) = psess.dcx().has_errors() {
+            return Err(e);
+        }
 
         source_file = match source_map.files().as_slice() {
             [file] => Arc::clone(file),
@@ -75,11 +165,7 @@ pub fn parse_module(
     let source_file =
         Arc::into_inner(source_file).expect("should have a unique reference to the SourceFile");
 
-    Ok(ParseModuleResult {
-        module,
-        source_file,
-        submodules,
-    })
+    Ok((output, source_file))
 }
 
 pub fn parse_no_errors<T>(
@@ -102,6 +188,14 @@ pub fn parse_no_errors<T>(
     }
 }
 
+/// Creates a `ParseSess` that shares the `SourceMap` of another and does not print errors
+pub fn silent_parse_sess(psess: &ParseSess) -> ParseSess {
+    let source_map = psess.clone_source_map();
+    let dcx = build_diag_ctxt(Arc::clone(&source_map));
+    dcx.make_silent();
+    ParseSess::with_dcx(dcx, source_map)
+}
+
 fn build_diag_ctxt(source_map: Arc<SourceMap>) -> DiagCtxt {
     let translator = rustc_driver::default_translator();
     let emitter = Box::new(
@@ -122,6 +216,12 @@ fn module_parser<'a>(psess: &'a ParseSess, source: CrateSource) -> Parser<'a> {
             source.to_owned(),
             StripTokens::Nothing,
         ),
+        CrateSource::SourceWithPath(source, path) => rustc_parse::new_parser_from_source_str(
+            psess,
+            FileName::from(path.to_path_buf()),
+            source.to_owned(),
+            StripTokens::Nothing,
+        ),
     };
     // todo is this unwrap okay?
     rustc_parse::unwrap_or_emit_fatal(parser)
@@ -130,6 +230,7 @@ fn module_parser<'a>(psess: &'a ParseSess, source: CrateSource) -> Parser<'a> {
 #[cfg(test)]
 mod tests {
     use crate::CrateSource;
+    use crate::config::MacroTable;
     use crate::parse::parse_module;
     use crate::util::rustc::init_rustc_globals;
     use rustc_span::Symbol;
@@ -142,6 +243,7 @@ mod tests {
             let module = parse_module(
                 CrateSource::File(Path::new("tests/submodules_tests/non_relative/main.rs")),
                 None,
+                &MacroTable::default(),
             )
             .unwrap();
             let expected = &[
@@ -189,6 +291,7 @@ mod tests {
             let module = parse_module(
                 CrateSource::File(Path::new("tests/submodules_tests/relative/main.rs")),
                 Some(Ident::with_dummy_span(Symbol::intern("main"))),
+                &MacroTable::default(),
             )
             .unwrap();
             let expected = &[
@@ -0,0 +1,19 @@
+use rustc_ast::ast;
+
+/// A standalone fragment of code, as opposed to a whole module
+pub enum Snippet {
+    Block(Box<ast::Block>),
+    Expr(Box<ast::Expr>),
+    Item(Box<ast::Item>),
+    Pat(Box<ast::Pat>),
+    Ty(Box<ast::Ty>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SnippetKind {
+    Block,
+    Expr,
+    Item,
+    Pat,
+    Ty,
+}
@@ -3,6 +3,7 @@ mod source_reader;
 mod whitespace_and_comments;
 
 use self::source_reader::SourceReader;
+use self::whitespace_and_comments::find_format_off_regions;
 use crate::constraint_writer::ConstraintWriter;
 use crate::constraints::Constraints;
 use crate::error::FormatResult;
@@ -24,6 +25,8 @@ pub struct SourceFormatter {
     out: ConstraintWriter,
     /// The number of spaces for the current level of indentation
     pub total_indent: Cell<HSize>,
+    /// Regions between `// myrustfmt: off` and `// myrustfmt: on` comments
+    format_off_regions: Vec<Span>,
 }
 
 macro_rules! delegate_to_constraint_writer {
@@ -42,7 +45,10 @@ delegate_to_constraint_writer! {
     pub fn constraints(&self) -> &Constraints;
     pub fn line(&self) -> VSize;
     pub fn col(&self) -> HSize;
+    pub fn len(&self) -> usize;
     pub fn line_col(&self) -> (VSize, HSize);
+    pub fn char_col(&self) -> HSize;
+    pub fn with_output_since<T>(&self, pos: usize, f: impl FnOnce(&str) -> T) -> T;
     pub fn with_last_line<T>(&self, f: impl FnOnce(&str) -> T) -> T;
 
     #[allow(unused)]
@@ -58,18 +64,33 @@ impl SourceFormatter {
         source_file: Arc<SourceFile>,
         error_emitter: Rc<BufferedErrorEmitter>,
         max_width: HSize,
+        error_on_line_overflow: bool,
     ) -> SourceFormatter {
         let source_reader = SourceReader::new(path, source_file);
         let capacity = source_reader.source().len() * 2;
-        let out = ConstraintWriter::new(max_width, Rc::clone(&error_emitter), capacity);
+        let out = ConstraintWriter::new(
+            max_width,
+            error_on_line_overflow,
+            Rc::clone(&error_emitter),
+            capacity,
+        );
+        let format_off_regions = find_format_off_regions(source_reader.source());
         SourceFormatter {
             error_emitter,
             source_reader,
             out,
             total_indent: Cell::new(0),
+            format_off_regions,
         }
     }
 
+    /// Returns true if the span is within a `// myrustfmt: off` region
+    pub fn is_format_off(&self, span: &Span) -> bool {
+        self.format_off_regions
+            .iter()
+            .any(|region| region.lo <= span.lo && span.hi <= region.hi)
+    }
+
     pub fn finish(self) -> String {
         self.source_reader.finish();
         self.out.finish()
@@ -176,6 +197,54 @@ impl SourceFormatter {
         Ok(())
     }
 
+    /// Skips a span of source and writes the given text in its place
+    pub fn replace_span(&self, span: Span, text: &str) -> FormatResult {
+        if self.source_reader.pos() < span.lo {
+            self.horizontal_whitespace()?;
+        }
+        self.source_reader.eat_span(span);
+        self.out.write_str(text)?;
+        Ok(())
+    }
+
+    /// Copies a literal, which may be a string literal with newlines
+    pub fn copy_literal(&self, span: Span) -> FormatResult {
+        if self.source_reader.pos() < span.lo {
+            self.horizontal_whitespace()?;
+        }
+        let segment = self.source_reader.eat_span(span);
+        if segment.contains('\n') {
+            self.out.multi_line_token(segment)?;
+        } else {
+            self.out.unbreakable_token(segment)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a literal in place of the one in source, which may be a string literal with newlines
+    pub fn replace_literal(&self, span: Span, literal: &str) -> FormatResult {
+        if self.source_reader.pos() < span.lo {
+            self.horizontal_whitespace()?;
+        }
+        self.source_reader.eat_span(span);
+        if literal.contains('\n') {
+            self.out.multi_line_token(literal)?;
+        } else {
+            self.out.unbreakable_token(literal)?;
+        }
+        Ok(())
+    }
+
+    /// Skips source that is removed by a rewrite. See `SourceReader::eat_removed_span`.
+    pub fn skip_removed_span(&self, span: Span) {
+        self.source_reader.eat_removed_span(span);
+    }
+
+    /// Copies the rest of the source without enforcing constraints
+    pub fn copy_remaining(&self) {
+        self.copy_unchecked(self.source_reader.remaining().len().try_into().unwrap());
+    }
+
     /// Copies a segment from source without enforcing constraints
     fn copy_unchecked(&self, len: u32) {
         let segment = self.source_reader.eat_len(len);
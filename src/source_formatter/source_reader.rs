@@ -1,6 +1,7 @@
 use crate::error::{ParseError, panic_parse_error};
 use crate::span::Span;
 use crate::util::line_col::line_col;
+use crate::util::whitespace_utils::contains_comment;
 use rustc_lexer::FrontmatterAllowed;
 use rustc_span::{BytePos, Pos, SourceFile};
 use std::cell::Cell;
@@ -83,6 +84,17 @@ impl SourceReader {
         self.eat_len(len)
     }
 
+    /// Skips source that is removed by a rewrite, like format arguments that are inlined into the
+    /// format string. This is the one exception to every source token being written to the output,
+    /// so comments must not be removed.
+    pub fn eat_removed_span(&self, span: Span) {
+        let removed = self.eat_span(span);
+        assert!(
+            !contains_comment(removed),
+            "removed source contains a comment",
+        );
+    }
+
     pub fn eat_token(&self, token: &'static str) {
         if !self.try_eat_token(token) {
             self.parse_error(ParseError::ExpectedToken(token));
@@ -1,7 +1,9 @@
 use crate::error::{FormatErrorKind, FormatResult, VerticalError};
 use crate::source_formatter::SourceFormatter;
+use crate::span::Span;
 use crate::whitespace::VerticalWhitespaceMode;
 use rustc_lexer::{FrontmatterAllowed, TokenKind};
+use rustc_span::BytePos;
 
 impl SourceFormatter {
     /// Allows any comments or nothing.
@@ -272,6 +274,37 @@ impl WhitespaceContext<'_> {
     }
 }
 
+/// Finds regions from a `// myrustfmt: off` comment to a `// myrustfmt: on` comment, or to the end
+/// of the file if there is no `on` comment
+pub(super) fn find_format_off_regions(source: &str) -> Vec<Span> {
+    let mut regions = Vec::new();
+    let mut off_lo = None;
+    let mut pos = 0;
+    for token in rustc_lexer::tokenize(source, FrontmatterAllowed::No) {
+        let token_str = &source[pos..][..token.len as usize];
+        let lo = BytePos(pos.try_into().unwrap());
+        pos += token.len as usize;
+        let TokenKind::LineComment { doc_style: None } = token.kind else {
+            continue;
+        };
+        match token_str["//".len()..].trim() {
+            "myrustfmt: off" if off_lo.is_none() => off_lo = Some(lo),
+            "myrustfmt: on" => {
+                if let Some(lo) = off_lo.take() {
+                    let hi = BytePos(pos.try_into().unwrap());
+                    regions.push(Span { lo, hi });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(lo) = off_lo {
+        let hi = BytePos(source.len().try_into().unwrap());
+        regions.push(Span { lo, hi });
+    }
+    regions
+}
+
 fn tokenize_whitespace_and_comments(source: &str) -> impl Iterator<Item = (&str, bool, bool)> {
     let mut cursor = rustc_lexer::Cursor::new(source, FrontmatterAllowed::No);
     std::iter::from_fn(move || {
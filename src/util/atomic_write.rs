@@ -0,0 +1,63 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Replaces the contents of an existing file by writing to a temporary file in the same directory
+/// and renaming it over the original, so that an interrupted write does not leave a partially
+/// written file. The permissions of the original file are kept. A symlink is resolved so that its
+/// target is written, and a file with other hard links is written in place so that the links are
+/// kept.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let path = fs::canonicalize(path)?;
+    let metadata = fs::metadata(&path)?;
+    let permissions = metadata.permissions();
+    if permissions.readonly() {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "the file is read-only"));
+    }
+    if has_other_hard_links(&metadata) {
+        return fs::write(&path, contents);
+    }
+    let (temp_path, mut file) = create_temp_file(&path)?;
+    let result = (|| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(&temp_path, permissions)?;
+        fs::rename(&temp_path, &path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(unix)]
+fn has_other_hard_links(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_other_hard_links(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Creates a new file at `dir/.file.rs.<pid>.<n>.myrustfmt.tmp`, trying the next `n` if the file
+/// already exists
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, fs::File)> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    loop {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let mut file_name = OsString::from(".");
+        file_name.push(path.file_name().expect("path should have a file name"));
+        file_name.push(format!(".{}.{n}.myrustfmt.tmp", process::id()));
+        let temp_path = path.with_file_name(file_name);
+        match fs::File::create_new(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -1,3 +1,23 @@
+use unicode_width::UnicodeWidthChar;
+
 pub fn is_closer_char(c: u8) -> bool {
     matches!(c, b'(' | b')' | b']' | b'}' | b'?' | b'>')
 }
+
+/// The number of columns a character occupies when displayed. East Asian wide characters take two
+/// columns and combining marks take none.
+pub fn char_width(c: char) -> usize {
+    if c.is_ascii() {
+        1
+    } else {
+        c.width().unwrap_or(0)
+    }
+}
+
+/// The number of bytes in the string minus its display width
+pub fn width_excess(str: &str) -> usize {
+    if str.is_ascii() {
+        return 0;
+    }
+    str.chars().map(|c| c.len_utf8() - char_width(c)).sum()
+}
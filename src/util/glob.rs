@@ -0,0 +1,42 @@
+/// Matches a `/`-separated path against a glob pattern. `*` and `?` match within one path
+/// component, and a `**` component matches any number of components.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = Vec::from_iter(pattern.split('/').filter(|c| !c.is_empty()));
+    let path = Vec::from_iter(path.split('/').filter(|c| !c.is_empty()));
+    components_match(&pattern, &path)
+}
+
+fn components_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ["**", rest @ ..] => (0..=path.len()).any(|i| components_match(rest, &path[i..])),
+        [first, rest @ ..] => match path {
+            [] => false,
+            [path_first, path_rest @ ..] => {
+                wildcard_match(first, path_first) && components_match(rest, path_rest)
+            }
+        },
+    }
+}
+
+/// Matches a single path component against a pattern with `*` and `?` wildcards
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    match pattern.chars().next() {
+        None => name.is_empty(),
+        Some('*') => {
+            let rest = &pattern[1..];
+            name.char_indices().map(|(i, _)| i).chain([name.len()]).any(
+                |i| wildcard_match(rest, &name[i..]),
+            )
+        }
+        Some(c) => {
+            let mut name_chars = name.chars();
+            match name_chars.next() {
+                Some(n) if c == '?' || c == n => {
+                    wildcard_match(&pattern[c.len_utf8()..], name_chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
@@ -1,8 +1,10 @@
+pub mod atomic_write;
 pub mod cell_ext;
 pub mod chars;
 pub mod cmp;
 pub mod display;
 pub mod drop;
+pub mod glob;
 pub mod line_col;
 pub mod rustc;
 pub mod whitespace_utils;
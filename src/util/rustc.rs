@@ -5,3 +5,8 @@ const RUSTC_EDITION: Edition = Edition::Edition2024;
 pub fn init_rustc_globals<T>(f: impl FnOnce() -> T) -> T {
     rustc_span::create_session_globals_then(RUSTC_EDITION, &[], None, f)
 }
+
+/// Like `init_rustc_globals`, but uses the existing globals if they are already set for this thread
+pub fn with_rustc_globals<T>(f: impl FnOnce() -> T) -> T {
+    rustc_span::create_session_if_not_set_then(RUSTC_EDITION, |_| f())
+}
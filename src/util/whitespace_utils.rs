@@ -9,6 +9,13 @@ pub fn is_whitespace_or_semicolon(str: &str) -> bool {
     str.chars().all(|c| c == ';' || rustc_lexer::is_whitespace(c))
 }
 
+pub fn contains_comment(str: &str) -> bool {
+    rustc_lexer::tokenize(str, FrontmatterAllowed::No).any(|token| matches!(
+        token.kind,
+        TokenKind::BlockComment { .. } | TokenKind::LineComment { .. },
+    ))
+}
+
 pub fn expect_first_token_after_whitespace_and_comments(str: &str, token_kind: TokenKind) -> u32 {
     let (dist, found) = first_token_after_whitespace_and_comments(str);
     if found != token_kind {
@@ -0,0 +1,126 @@
+#![feature(rustc_private)]
+
+use myrustfmt::cargo_workspace::Workspace;
+use myrustfmt::config::Edition;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const ROOT: &str = "tests/cargo_workspace_tests";
+
+fn package_summary(workspace: &Workspace, index: usize) -> (String, Vec<String>) {
+    let package = &workspace.packages[index];
+    let crate_roots = Vec::from_iter(package.crate_roots.iter().map(|path| {
+        path.strip_prefix(&package.dir)
+            .unwrap()
+            .display()
+            .to_string()
+    }));
+    (package.name.clone(), crate_roots)
+}
+
+fn names(workspace: &Workspace, indices: &[usize]) -> Vec<String> {
+    Vec::from_iter(indices.iter().map(|&i| workspace.packages[i].name.clone()))
+}
+
+#[test]
+fn from_workspace_root() {
+    let workspace = Workspace::find(Path::new(ROOT)).unwrap();
+    let all = Vec::from_iter(0..workspace.packages.len());
+    assert_eq!(names(&workspace, &all), ["root", "a", "b"]);
+    assert_eq!(names(&workspace, &workspace.current), ["root"]);
+    assert_eq!(
+        package_summary(&workspace, 0),
+        ("root".to_string(), vec!["src/lib.rs".to_string()]),
+    );
+    assert_eq!(
+        package_summary(&workspace, 1),
+        (
+            "a".to_string(),
+            Vec::from_iter(
+                [
+                    "benches/b.rs",
+                    "examples/ex/main.rs",
+                    "src/bin/extra.rs",
+                    "src/main.rs",
+                    "tests/it.rs",
+                    "tools/tool.rs",
+                ]
+                .map(String::from),
+            ),
+        ),
+    );
+    assert_eq!(
+        package_summary(&workspace, 2),
+        ("b".to_string(), vec!["lib.rs".to_string()]),
+    );
+}
+
+#[test]
+fn from_member_subdirectory() {
+    let workspace = Workspace::find(&Path::new(ROOT).join("crates/a/src")).unwrap();
+    assert_eq!(workspace.packages.len(), 3);
+    assert_eq!(names(&workspace, &workspace.current), ["a"]);
+    assert!(workspace.package_by_name("b").is_some());
+}
+
+#[test]
+fn excluded_member() {
+    let workspace = Workspace::find(&Path::new(ROOT).join("crates/excluded")).unwrap();
+    let all = Vec::from_iter(0..workspace.packages.len());
+    assert_eq!(names(&workspace, &all), ["excluded"]);
+    assert_eq!(names(&workspace, &workspace.current), ["excluded"]);
+}
+
+#[test]
+fn editions() {
+    let workspace = Workspace::find(Path::new(ROOT)).unwrap();
+    let editions = Vec::from_iter(workspace.packages.iter().map(|p| p.edition));
+    assert_eq!(
+        editions,
+        [
+            Edition::Edition2015,
+            Edition::Edition2021,
+            Edition::Edition2018,
+        ],
+    );
+}
+
+#[test]
+fn all_with_package() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-myrustfmt"))
+        .args(["myrustfmt", "--check", "--all", "-p", "a"])
+        .current_dir(ROOT)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "--all cannot be used with --package\n",
+    );
+}
+
+#[test]
+fn package_edition_enables_rewrites() {
+    let dir = std::env::temp_dir().join(format!("myrustfmt-edition-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"p\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    let lib = dir.join("src/lib.rs");
+    fs::write(&lib, "fn f() {\n    panic!(\"{}\", x);\n}\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-myrustfmt"))
+        .args(["myrustfmt", "--inline-format-args"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&lib).unwrap(),
+        "fn f() {\n    panic!(\"{x}\");\n}\n",
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,217 @@
+#![feature(rustc_private)]
+
+use myrustfmt::config::{Config, Edition, MacroTable, Severity};
+use myrustfmt::formatter::Formatter;
+use std::thread;
+
+#[test]
+fn shared_across_threads() {
+    let formatter = Formatter::new(Config::default());
+    thread::scope(|scope| {
+        let handles = Vec::from_iter((0..4).map(|i| {
+            let formatter = &formatter;
+            scope.spawn(move || {
+                let source = format!("fn f{i}( ) {{ foo( a,b ); }}");
+                formatter.format_str(&source).unwrap().expect_no_errors()
+            })
+        }));
+        for (i, handle) in handles.into_iter().enumerate() {
+            let formatted = handle.join().unwrap();
+            assert_eq!(formatted, format!("fn f{i}() {{\n    foo(a, b);\n}}\n"));
+        }
+    });
+}
+
+#[test]
+fn repeated_calls_on_one_thread() {
+    let formatter = Formatter::new(Config::default());
+    let expr = formatter
+        .format_expr_str("a+b", 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(expr, "a + b");
+    let ty = formatter
+        .format_ty_str("Vec< u8 >", 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(ty, "Vec<u8>");
+}
+
+#[test]
+fn parse_failed() {
+    let formatter = Formatter::new(Config::default());
+    assert!(formatter.format_str("fn f() { a + }").is_err());
+}
+
+#[test]
+fn format_generated_files() {
+    let source = "// @generated\n\nfn f( ) {}\n";
+    let formatter = Formatter::new(Config::default());
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, source);
+    let formatter = Formatter::new(Config::default().format_generated_files(true));
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, "// @generated\n\nfn f() {}\n");
+}
+
+#[test]
+fn do_not_edit_marker() {
+    let source =
+        "//! Bindings for the C API\n//! DO NOT EDIT: this file is generated\n\nfn f( ) {}\n";
+    let formatter = Formatter::new(Config::default());
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, source);
+}
+
+#[test]
+fn error_on_line_overflow() {
+    let source = "fn f() {\n    let aaaa = bbbbbbbbbbbbbbbbbbbb;\n}\n";
+    let formatter = Formatter::new(Config::default().max_width(20));
+    assert_eq!(formatter.format_str(source).unwrap().error_count, 1);
+    let formatter = Formatter::new(
+        Config::default()
+            .max_width(20)
+            .error_on_line_overflow(false),
+    );
+    assert_eq!(formatter.format_str(source).unwrap().error_count, 0);
+}
+
+#[test]
+fn severity() {
+    let source = "fn f() {\n    let aaaa = bbbbbbbbbbbbbbbbbbbb;\n}\n";
+    let formatter = Formatter::new(
+        Config::default()
+            .max_width(20)
+            .max_width_exceeded(Severity::Warn),
+    );
+    assert_eq!(formatter.format_str(source).unwrap().error_count, 0);
+    let formatter = Formatter::new(
+        Config::default()
+            .max_width(20)
+            .max_width_exceeded(Severity::Allow),
+    );
+    assert_eq!(formatter.format_str(source).unwrap().error_count, 0);
+}
+
+#[test]
+fn macro_table() {
+    let macros = "info = { kind = \"format\" }\n\
+                  \"tracing::event\" = { kind = \"format\", format_string_pos = 1 }\n\
+                  define = { kind = \"items\" }\n\
+                  list = { kind = \"vec\" }\n";
+    let formatter = Formatter::new(Config::default().macros(macros.parse().unwrap()));
+    let source = "fn f() {\n\
+                  \x20   info!(target:\"app\",\"x = {}\",x);\n\
+                  \x20   log::info!( \"x\" );\n\
+                  \x20   tracing::event!(Level::INFO,\"x\");\n\
+                  \x20   event!(Level::INFO,\"x\");\n\
+                  \x20   let v = list!(1,2);\n\
+                  }\n\
+                  define! { struct   A; fn  b() {} }\n";
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, "fn f() {\n\
+         \x20   info!(target: \"app\", \"x = {}\", x);\n\
+         \x20   log::info!(\"x\");\n\
+         \x20   tracing::event!(Level::INFO, \"x\");\n\
+         \x20   event!(Level::INFO, \"x\");\n\
+         \x20   let v = list![1, 2];\n\
+         }\n\
+         define! {\n\
+         \x20   struct A;\n\
+         \x20   fn b() {}\n\
+         }\n");
+}
+
+#[test]
+fn invalid_macro_table() {
+    assert!(
+        "info = { kind = \"unknown\" }"
+            .parse::<MacroTable>()
+            .is_err(),
+    );
+    assert!(
+        "info = { kind = \"vec\", format_string_pos = 0 }"
+            .parse::<MacroTable>()
+            .is_err(),
+    );
+}
+
+#[test]
+fn inline_format_args() {
+    let formatter = Formatter::new(Config::default().inline_format_args(true));
+    let source = "fn f() {\n\
+                  \x20   println!(\"{} {:?} {:>5}\", a, b, c);\n\
+                  \x20   println!(\"{1} {0}\", a, b,);\n\
+                  \x20   assert_eq!(a, b, \"{}\", a);\n\
+                  \x20   println!(\"{}\", x.y);\n\
+                  \x20   println!(\"{0} {0}\", x);\n\
+                  \x20   println!(\"{:1$}\", x, width);\n\
+                  \x20   println!(\"{}\", /* x */ x);\n\
+                  }\n";
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, "fn f() {\n\
+         \x20   println!(\"{a} {b:?} {c:>5}\");\n\
+         \x20   println!(\"{b} {a}\");\n\
+         \x20   assert_eq!(a, b, \"{a}\");\n\
+         \x20   println!(\"{}\", x.y);\n\
+         \x20   println!(\"{0} {0}\", x);\n\
+         \x20   println!(\"{:1$}\", x, width);\n\
+         \x20   println!(\"{}\", /* x */ x);\n\
+         }\n");
+    let formatter = Formatter::new(Config::default());
+    let formatted = formatter
+        .format_str("fn f() {\n    println!(\"{}\", x);\n}\n")
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "fn f() {\n    println!(\"{}\", x);\n}\n");
+}
+
+#[test]
+fn inline_format_args_in_macro_rules() {
+    let formatter = Formatter::new(Config::default().inline_format_args(true));
+    let source = "macro_rules! m {\n\
+                  \x20   () => {\n\
+                  \x20       println!(\"{}\", x);\n\
+                  \x20   };\n\
+                  }\n";
+    let formatted = formatter.format_str(source).unwrap().expect_no_errors();
+    assert_eq!(formatted, "macro_rules! m {\n\
+         \x20   () => {\n\
+         \x20       println!(\"{x}\");\n\
+         \x20   };\n\
+         }\n");
+}
+
+#[test]
+fn inline_format_args_panic_edition() {
+    let source = "fn f() {\n\
+                  \x20   panic!(\"{}\", x);\n\
+                  \x20   assert!(c, \"{}\", x);\n\
+                  \x20   unreachable!(\"{}\", x);\n\
+                  \x20   todo!(\"{}\", x);\n\
+                  }\n";
+    let inlined = "fn f() {\n\
+                   \x20   panic!(\"{x}\");\n\
+                   \x20   assert!(c, \"{x}\");\n\
+                   \x20   unreachable!(\"{x}\");\n\
+                   \x20   todo!(\"{x}\");\n\
+                   }\n";
+    let not_inlined = "fn f() {\n\
+                       \x20   panic!(\"{}\", x);\n\
+                       \x20   assert!(c, \"{}\", x);\n\
+                       \x20   unreachable!(\"{}\", x);\n\
+                       \x20   todo!(\"{x}\");\n\
+                       }\n";
+    for (edition, expected) in [
+        (Edition::Unknown, not_inlined),
+        (Edition::Edition2018, not_inlined),
+        (Edition::Edition2021, inlined),
+    ] {
+        let config = Config::default().inline_format_args(true).edition(edition);
+        let formatted = Formatter::new(config)
+            .format_str(source)
+            .unwrap()
+            .expect_no_errors();
+        assert_eq!(formatted, expected, "{edition:?}");
+    }
+}
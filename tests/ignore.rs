@@ -0,0 +1,51 @@
+mod util;
+
+use crate::util::SimpleOutput;
+use std::process::Command;
+
+fn check(args: &[&str]) -> SimpleOutput {
+    let output = Command::new(env!("CARGO_BIN_EXE_myrustfmt"))
+        .current_dir("tests/ignore_tests")
+        .args(["--check", "-v"])
+        .args(args)
+        .arg("src/main.rs")
+        .output()
+        .unwrap();
+    SimpleOutput::expect(output)
+}
+
+#[test]
+fn ignore_glob() {
+    // src/proto/messages.rs is not formatted, so it would be a mismatch if checked
+    let output = check(&["--ignore", "src/proto/**"]);
+    assert_eq!(
+        output.stderr,
+        "Ok: src/main.rs\n\
+         Ok: src/child.rs\n\
+         Ok: src/proto.rs\n\
+         Skipped: src/proto/messages.rs (matches ignore glob `src/proto/**`)\n",
+    );
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn ignore_glob_with_wildcard() {
+    let output = check(&["--ignore", "src/*.rs"]);
+    assert_eq!(
+        output.stderr,
+        "Skipped: src/main.rs (matches ignore glob `src/*.rs`)\n",
+    );
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn skip_children() {
+    let output = check(&["--skip-children"]);
+    assert_eq!(
+        output.stderr,
+        "Ok: src/main.rs\n\
+         Skipped: src/child.rs (skip_children)\n\
+         Skipped: src/proto.rs (skip_children)\n",
+    );
+    assert_eq!(output.code, 0);
+}
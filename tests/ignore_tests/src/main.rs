@@ -0,0 +1,2 @@
+mod child;
+mod proto;
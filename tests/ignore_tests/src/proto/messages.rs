@@ -0,0 +1 @@
+pub struct Message { pub id : u32 }
@@ -0,0 +1,51 @@
+#![feature(rustc_private)]
+
+use myrustfmt::config::{Config, NewlineStyle};
+use myrustfmt::format_str;
+
+fn format(source: &str, newline_style: NewlineStyle) -> String {
+    format_str(source, Config::default().newline_style(newline_style))
+        .unwrap()
+        .expect_no_errors()
+}
+
+#[test]
+fn auto_keeps_crlf() {
+    let formatted = format("fn  f()  {\r\n  a;\r\n}\r\n", NewlineStyle::Auto);
+    assert_eq!(formatted, "fn f() {\r\n    a;\r\n}\r\n");
+}
+
+#[test]
+fn auto_keeps_lf() {
+    let formatted = format("fn  f()  {\n  a;\n}\n", NewlineStyle::Auto);
+    assert_eq!(formatted, "fn f() {\n    a;\n}\n");
+}
+
+#[test]
+fn unix_and_windows() {
+    let source = "fn  f()  {\r\n  a;\n}\r\n";
+    assert_eq!(format(source, NewlineStyle::Unix), "fn f() {\n    a;\n}\n");
+    assert_eq!(
+        format(source, NewlineStyle::Windows),
+        "fn f() {\r\n    a;\r\n}\r\n",
+    );
+}
+
+#[test]
+fn crlf_does_not_count_toward_width() {
+    let source = "fn f() {\r\n    let x = [aaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbb];\r\n}\r\n";
+    let formatted = format_str(source, Config::default().max_width(50));
+    assert_eq!(formatted.unwrap().expect_no_errors(), source);
+}
+
+#[test]
+fn keeps_bom() {
+    let formatted = format("\u{feff}fn  f()  {}\r\n", NewlineStyle::Auto);
+    assert_eq!(formatted, "\u{feff}fn f() {}\r\n");
+}
+
+#[test]
+fn bom_and_crlf_in_formatted_file_are_unchanged() {
+    let source = "\u{feff}// comment\r\nfn f() {\r\n    \"a\r\nb\";\r\n}\r\n";
+    assert_eq!(format(source, NewlineStyle::Auto), source);
+}
@@ -11,6 +11,7 @@ use std::process::{Command, Stdio};
 use std::sync::LazyLock;
 use std::{fs, io};
 use tracing_subscriber::EnvFilter;
+use unicode_width::UnicodeWidthStr;
 
 type TestResult<T = ()> = Result<T, Box<dyn Error>>;
 
@@ -35,8 +36,8 @@ fn output_test(path: &Path) -> TestResult {
 
 fn read_test(path: &Path) -> TestResult<Test> {
     let string = fs::read_to_string(path)?;
-    let (test_kind_raw, max_width, body) = parse_test_header(&string)?;
-    let kind = parse_test_body(test_kind_raw, max_width, body)?;
+    let (header, body) = parse_test_header(&string)?;
+    let kind = parse_test_body(header.test_kind, header.max_width, body)?;
     let name = path.file_stem().unwrap().to_str().unwrap().to_owned();
     let expected_stderr_path = path.with_extension("stderr");
     let expected_stderr = match fs::read_to_string(&expected_stderr_path) {
@@ -47,16 +48,18 @@ fn read_test(path: &Path) -> TestResult<Test> {
     Ok(Test {
         name,
         kind,
+        macros: header.macros,
         expected_stderr,
         expected_stderr_path,
     })
 }
 
-fn parse_test_header(string: &str) -> TestResult<(TestKindRaw, Option<u16>, &str)> {
+fn parse_test_header(string: &str) -> TestResult<(TestHeader, &str)> {
     let mut lines = string.split_inclusive('\n');
     let mut lines_peekable = lines.by_ref().peekable();
     let mut test_kind = None::<TestKindRaw>;
     let mut max_width = None::<u16>;
+    let mut macros = None::<String>;
     loop {
         let Some(&line) = lines_peekable.peek() else {
             break;
@@ -76,6 +79,7 @@ fn parse_test_header(string: &str) -> TestResult<(TestKindRaw, Option<u16>, &str
             "max-width" => {
                 max_width = Some(value.parse().map_err(|_| "invalid max-width value")?);
             }
+            "macros" => macros = Some(value.to_owned()),
             "note" => {}
             "test-kind" => {
                 if test_kind.is_some() {
@@ -102,7 +106,12 @@ fn parse_test_header(string: &str) -> TestResult<(TestKindRaw, Option<u16>, &str
         ),
     }
     let body = lines.remainder().unwrap_or("");
-    Ok((test_kind_raw, max_width, body))
+    let header = TestHeader {
+        test_kind: test_kind_raw,
+        max_width,
+        macros,
+    };
+    Ok((header, body))
 }
 
 fn parse_test_body(
@@ -177,7 +186,7 @@ fn run_test(test: &Test) -> TestResult {
             ref after,
         } => {
             assert!(test.expected_stderr.is_none());
-            breakpoint_test(before, after, None, None)?
+            breakpoint_test(before, after, test.macros.as_deref(), None, None)?
         }
         TestKind::BreakpointError { ref formatted } => {
             let expected_stderr = test.expected_stderr.as_deref().expect(
@@ -186,6 +195,7 @@ fn run_test(test: &Test) -> TestResult {
             breakpoint_test(
                 formatted,
                 formatted,
+                test.macros.as_deref(),
                 Some(expected_stderr),
                 Some(&test.expected_stderr_path),
             )?
@@ -198,6 +208,7 @@ fn run_test(test: &Test) -> TestResult {
             format_max_width_expected(
                 formatted,
                 max_width,
+                test.macros.as_deref(),
                 formatted,
                 "formatted",
                 test.expected_stderr.as_deref(),
@@ -214,6 +225,7 @@ fn run_test(test: &Test) -> TestResult {
             format_max_width_expected(
                 before,
                 max_width,
+                test.macros.as_deref(),
                 after,
                 "before -> after",
                 test.expected_stderr.as_deref(),
@@ -222,6 +234,7 @@ fn run_test(test: &Test) -> TestResult {
             format_max_width_expected(
                 after,
                 max_width,
+                test.macros.as_deref(),
                 after,
                 "after (idempotency)",
                 test.expected_stderr.as_deref(),
@@ -232,9 +245,17 @@ fn run_test(test: &Test) -> TestResult {
     Ok(())
 }
 
+struct TestHeader {
+    test_kind: TestKindRaw,
+    max_width: Option<u16>,
+    macros: Option<String>,
+}
+
 struct Test {
     name: String,
     kind: TestKind,
+    /// The `--macros` option
+    macros: Option<String>,
     expected_stderr: Option<String>,
     expected_stderr_path: PathBuf,
 }
@@ -269,15 +290,21 @@ enum TestKindRaw {
 fn breakpoint_test(
     before: &str,
     after: &str,
+    macros: Option<&str>,
     expected_stderr_after: Option<&str>,
     expected_stderr_after_path: Option<&Path>,
 ) -> TestResult {
     let before = before.trim();
     let after = after.trim();
-    let initial_used_width = before.lines().map(|line| line.len() as u16).max().unwrap();
+    let initial_used_width = before
+        .lines()
+        .map(|line| line.width() as u16)
+        .max()
+        .unwrap();
     format_max_width_expected(
         before,
         Some(initial_used_width),
+        macros,
         before,
         "before max width reduction",
         None,
@@ -287,6 +314,7 @@ fn breakpoint_test(
     format_max_width_expected(
         before,
         Some(initial_used_width - 1),
+        macros,
         after,
         "after max width reduction",
         expected_stderr_after,
@@ -298,6 +326,7 @@ fn breakpoint_test(
 fn format_max_width_expected(
     source: &str,
     max_width: Option<u16>,
+    macros: Option<&str>,
     expected: &str,
     name: &str,
     expected_stderr: Option<&str>,
@@ -308,6 +337,10 @@ fn format_max_width_expected(
         args.push("--max-width".to_string());
         args.push(max_width.to_string());
     }
+    if let Some(macros) = macros {
+        args.push("--macros".to_string());
+        args.push(macros.to_string());
+    }
     args.push("-".to_string());
     let mut child = Command::new(env!("CARGO_BIN_EXE_myrustfmt"))
         .args(args)
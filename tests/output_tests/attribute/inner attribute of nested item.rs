@@ -0,0 +1,74 @@
+// test-kind: before-after
+
+fn f() {
+    #![allow(unused)]
+    let  x = 1;
+}
+
+fn g() { #![allow(unused)] }
+
+mod m { #![allow(dead_code)] }
+
+mod n {
+    //! Doc
+    #![allow(dead_code)]
+
+    fn a() {}
+}
+
+impl S {
+    #![allow(x)]
+    fn a() {}
+}
+
+trait T { #![allow(x)] #![allow(y)] }
+
+extern "C" {
+    #![allow(x)]
+    fn b();
+}
+
+fn h(x: [u8; { 1 }]) {
+    #![allow(x)]
+}
+
+// :after:
+
+fn f() {
+    #![allow(unused)]
+    let x = 1;
+}
+
+fn g() {
+    #![allow(unused)]
+}
+
+mod m {
+    #![allow(dead_code)]
+}
+
+mod n {
+    //! Doc
+    #![allow(dead_code)]
+
+    fn a() {}
+}
+
+impl S {
+    #![allow(x)]
+    fn a() {}
+}
+
+trait T {
+    #![allow(x)]
+    #![allow(y)]
+}
+
+extern "C" {
+    #![allow(x)]
+    fn b();
+}
+
+fn h(x: [u8; { 1 }]) {
+    #![allow(x)]
+}
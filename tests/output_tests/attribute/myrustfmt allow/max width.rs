@@ -0,0 +1,16 @@
+// test-kind: no-change
+// max-width: 40
+
+#[myrustfmt::allow(max_width)]
+fn test() {
+    let x =
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa;
+}
+
+fn test() {
+    #[myrustfmt::allow(max_width)]
+    let x =
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa;
+    let y =
+        bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb;
+}
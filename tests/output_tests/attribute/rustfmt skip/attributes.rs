@@ -0,0 +1,17 @@
+// test-kind: before-after
+
+#[rustfmt::skip::attributes(custom)]
+mod m {
+    #[custom(  a,b )]
+    #[other(  a,b )]
+    fn f() {}
+}
+
+// :after:
+
+#[rustfmt::skip::attributes(custom)]
+mod m {
+    #[custom(  a,b )]
+    #[other(a, b)]
+    fn f() {}
+}
@@ -0,0 +1,17 @@
+// test-kind: before-after
+
+mod m {
+    #![rustfmt::skip]
+    fn  f( ) {}
+}
+
+fn  g( ) {}
+
+// :after:
+
+mod m {
+    #![rustfmt::skip]
+    fn  f( ) {}
+}
+
+fn g() {}
@@ -0,0 +1,10 @@
+// test-kind: no-change
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn  f( ) {}
+
+#[cfg_attr(rustfmt, rustfmt::skip)]
+fn  g( ) {}
+
+#[rustfmt_skip]
+fn  h( ) {}
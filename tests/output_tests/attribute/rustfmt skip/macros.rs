@@ -0,0 +1,23 @@
+// test-kind: before-after
+
+#[rustfmt::skip::macros(vec)]
+fn f( ) {
+    vec!( 1,2 );
+    assert!( a,b );
+}
+
+fn g() {
+    vec!( 1,2 );
+}
+
+// :after:
+
+#[rustfmt::skip::macros(vec)]
+fn f() {
+    vec!( 1,2 );
+    assert!(a, b);
+}
+
+fn g() {
+    vec![1, 2];
+}
@@ -0,0 +1,63 @@
+// test-kind: before-after
+
+fn main() {
+    let a  =  1;
+    // myrustfmt: off
+    let b  =  [1,2,
+               3,4];
+    foo(  b  );
+    // myrustfmt: on
+    let c  =  2;
+    match x {
+        // myrustfmt: off
+        A  =>  1,
+        // myrustfmt: on
+        B  =>  2,
+    }
+    let v = [
+        1,
+        // myrustfmt: off
+        2  +  3,
+        // myrustfmt: on
+        4  ,
+    ];
+}
+
+// myrustfmt: off
+#[derive(Debug)]
+struct   S  {  a : u8  }
+// myrustfmt: on
+
+fn   g()  {}
+
+// :after:
+
+fn main() {
+    let a = 1;
+    // myrustfmt: off
+    let b  =  [1,2,
+               3,4];
+    foo(  b  );
+    // myrustfmt: on
+    let c = 2;
+    match x {
+        // myrustfmt: off
+        A  =>  1,
+        // myrustfmt: on
+        B => 2,
+    }
+    let v = [
+        1,
+        // myrustfmt: off
+        2  +  3,
+        // myrustfmt: on
+        4,
+    ];
+}
+
+// myrustfmt: off
+#[derive(Debug)]
+struct   S  {  a : u8  }
+// myrustfmt: on
+
+fn g() {}
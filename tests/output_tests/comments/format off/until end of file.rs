@@ -0,0 +1,17 @@
+// test-kind: before-after
+
+fn   f()  {}
+
+// myrustfmt: off
+fn   g()  {}
+
+fn   h()  {}
+
+// :after:
+
+fn f() {}
+
+// myrustfmt: off
+fn   g()  {}
+
+fn   h()  {}
@@ -0,0 +1,19 @@
+// test-kind: before-after
+
+fn test() {
+    become   f(x);
+    match x {
+        _ => {
+            become f(x);
+        }
+    }
+}
+
+// :after:
+
+fn test() {
+    become f(x);
+    match x {
+        _ => become f(x),
+    }
+}
@@ -0,0 +1,23 @@
+// test-kind: before-after
+
+fn test() {
+    let x = gen   {   yield 1;   };
+    let x = gen move { yield a };
+    let x = async   gen { yield a.await };
+    let x = async move {
+        let a = a.await;
+        a + 1
+    };
+}
+
+// :after:
+
+fn test() {
+    let x = gen { yield 1 };
+    let x = gen move { yield a };
+    let x = async gen { yield a.await };
+    let x = async move {
+        let a = a.await;
+        a + 1
+    };
+}
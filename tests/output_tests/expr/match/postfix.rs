@@ -0,0 +1,31 @@
+// test-kind: before-after
+
+fn test() {
+    x.match   {
+        Some(y) => y,
+        None => 0,
+    };
+    self.aaaaaaa.bbbbbbbb().match { _ => {} };
+    let x = aaaaaaaaaaaaaaaaaaaaaa.bbbbbbbbbbbbbbbbbbbbbbbbbbb.ccccccccccccccccccccccc().dddddddddd.match {
+        _ => 0,
+    };
+}
+
+// :after:
+
+fn test() {
+    x.match {
+        Some(y) => y,
+        None => 0,
+    };
+    self.aaaaaaa.bbbbbbbb().match {
+        _ => {}
+    };
+    let x = aaaaaaaaaaaaaaaaaaaaaa
+        .bbbbbbbbbbbbbbbbbbbbbbbbbbb
+        .ccccccccccccccccccccccc()
+        .dddddddddd
+        .match {
+            _ => 0,
+        };
+}
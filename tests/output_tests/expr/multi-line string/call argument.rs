@@ -0,0 +1,10 @@
+// test-kind: no-change
+
+fn test() {
+    foo(a, "one
+two", b);
+    bar(r#"
+        x
+    "#);
+}
+
@@ -0,0 +1,8 @@
+// test-kind: no-change
+// max-width: 30
+
+fn test() {
+    let x = "
+a line that is longer than the max width
+";
+}
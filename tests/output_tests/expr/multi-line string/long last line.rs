@@ -0,0 +1,18 @@
+// test-kind: before-after
+// max-width: 30
+
+fn test() {
+    foo(a, "
+a line that is long", bbbbbbbbbbb);
+}
+
+// :after:
+
+fn test() {
+    foo(
+        a,
+        "
+a line that is long",
+        bbbbbbbbbbb,
+    );
+}
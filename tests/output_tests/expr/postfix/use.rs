@@ -0,0 +1,16 @@
+// test-kind: before-after
+
+fn test() {
+    let x = a.use;
+    let x = aaaaaaaaaaaaaaaaaaaaaaaaaaaaa.bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb().cccccccccccccccccc.use;
+}
+
+// :after:
+
+fn test() {
+    let x = a.use;
+    let x = aaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+        .bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb()
+        .cccccccccccccccccc
+        .use;
+}
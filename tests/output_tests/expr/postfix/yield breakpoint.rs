@@ -0,0 +1,12 @@
+// test-kind: breakpoint
+
+fn test() {
+    aaaaaaaaa.yield;
+}
+
+// :after:
+
+fn test() {
+    aaaaaaaaa
+        .yield;
+}
@@ -0,0 +1,21 @@
+// test-kind: before-after
+
+fn test() {
+    let x: Option<_> = try   {   a?   };
+    let x: Option<_> = try {
+        let a = a?;
+        a + 1
+    };
+    try {}
+}
+
+// :after:
+
+fn test() {
+    let x: Option<_> = try { a? };
+    let x: Option<_> = try {
+        let a = a?;
+        a + 1
+    };
+    try {}
+}
@@ -0,0 +1,12 @@
+// test-kind: no-change
+// max-width: 40
+
+fn test() {
+    foo(
+        a,
+        "https://example.com/a/long/url/index.html",
+    );
+    let data = include_str!(
+        "a/long/path/to/a/file.txt",
+    );
+}
@@ -0,0 +1,4 @@
+// test-kind: no-change
+// max-width: 40
+
+const URL: &str = "https://example.com/a/long/url/index.html";
@@ -0,0 +1,15 @@
+// test-kind: breakpoint
+
+fn test() {
+    foo(aaaaaaaa, "café", "ééé");
+}
+
+// :after:
+
+fn test() {
+    foo(
+        aaaaaaaa,
+        "café",
+        "ééé",
+    );
+}
@@ -0,0 +1,14 @@
+// test-kind: breakpoint
+
+fn test() {
+    foo(aaaaaaaa, "🦀🦀🦀🦀");
+}
+
+// :after:
+
+fn test() {
+    foo(
+        aaaaaaaa,
+        "🦀🦀🦀🦀",
+    );
+}
@@ -0,0 +1,5 @@
+// test-kind: no-change
+
+fn f() {
+    let (日本, box x) = y;
+}
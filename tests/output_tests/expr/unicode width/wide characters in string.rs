@@ -0,0 +1,14 @@
+// test-kind: breakpoint
+
+fn test() {
+    foo(aaaaaaaa, "漢字漢字漢字");
+}
+
+// :after:
+
+fn test() {
+    foo(
+        aaaaaaaa,
+        "漢字漢字漢字",
+    );
+}
@@ -0,0 +1,13 @@
+// test-kind: before-after
+
+fn test() {
+    let x = builtin #  wrap_binder(x);
+    let x = builtin # unwrap_binder( x,  T );
+}
+
+// :after:
+
+fn test() {
+    let x = builtin # wrap_binder(x);
+    let x = builtin # unwrap_binder(x, T);
+}
@@ -0,0 +1,27 @@
+// test-kind: before-after
+
+fn test() {
+    yield;
+    yield   x;
+    x.yield;
+    do   yeet;
+    do yeet   x;
+    match x {
+        _ => {
+            yield x;
+        }
+    }
+}
+
+// :after:
+
+fn test() {
+    yield;
+    yield x;
+    x.yield;
+    do yeet;
+    do yeet x;
+    match x {
+        _ => yield x,
+    }
+}
@@ -0,0 +1,11 @@
+// test-kind: before-after
+
+fn f( ) {}
+
+// @generated
+
+// :after:
+
+fn f() {}
+
+// @generated
@@ -0,0 +1,35 @@
+// test-kind: before-after
+
+macro_rules! foo {
+    ($a:expr) => { $a+1 };
+    ($a:expr, $b:ident)=>( let $b = $a; );
+    // comment
+    () => {
+        fn  helper ( ) -> u32 { 1 }
+        struct S;
+    }
+}
+
+macro_rules! empty { () => {} }
+
+// :after:
+
+macro_rules! foo {
+    ($a:expr) => {
+        $a + 1
+    };
+    ($a:expr, $b:ident) => {
+        let $b = $a;
+    };
+    // comment
+    () => {
+        fn helper() -> u32 {
+            1
+        }
+        struct S;
+    };
+}
+
+macro_rules! empty {
+    () => {};
+}
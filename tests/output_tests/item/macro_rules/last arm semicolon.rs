@@ -0,0 +1,25 @@
+// test-kind: before-after
+
+macro_rules! foo {
+    () => {};
+}
+
+macro_rules! bar {
+    ($a:expr) => {
+        $a
+    };
+    () => {}
+}
+
+// :after:
+
+macro_rules! foo {
+    () => {};
+}
+
+macro_rules! bar {
+    ($a:expr) => {
+        $a
+    };
+    () => {};
+}
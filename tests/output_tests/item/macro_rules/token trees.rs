@@ -0,0 +1,23 @@
+// test-kind: no-change
+
+macro_rules! repetition {
+    ($($x:expr),* $(,)?) => {
+        vec![$($x),*]
+    };
+}
+
+macro_rules! not_rust {
+    ($vis:vis $name:ident) => {
+        $vis struct $name   ;
+    };
+}
+
+macro_rules! parens ( () => () );
+
+fn f() {
+    macro_rules! inner {
+        ($e:expr, $crate_name:ident) => {
+            $crate::call($e, "$e")
+        };
+    }
+}
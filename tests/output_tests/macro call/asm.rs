@@ -0,0 +1,62 @@
+// test-kind: before-after
+
+global_asm!(".globl foo",  "foo:", "ret");
+
+extern "C" fn f() {
+    core::arch::naked_asm!("ret",options(  att_syntax ));
+}
+
+fn f() {
+    unsafe {
+        asm!("nop");
+        asm!("mov {0}, {1}", out(reg) x, in(reg) y);
+        asm!("mov {tmp}, {x}", "shl {tmp}, 1", "shl {x}, 2", "add {x}, {tmp}", x = inout(reg) x, tmp = out(reg) _);
+        std::arch::asm!("syscall", in("rax") 1, inlateout("rdi") a => b, lateout("rcx") _, options(nostack,preserves_flags), clobber_abi("C", "system"));
+        asm!("call {f}", f = sym foo::<u8>, const N + 1, #[cfg(x)] in(reg) y);
+        asm!("jmp {}", label { return; });
+    }
+}
+
+// :after:
+
+global_asm!(".globl foo", "foo:", "ret");
+
+extern "C" fn f() {
+    core::arch::naked_asm!("ret", options(att_syntax));
+}
+
+fn f() {
+    unsafe {
+        asm!("nop");
+        asm!("mov {0}, {1}", out(reg) x, in(reg) y);
+        asm!(
+            "mov {tmp}, {x}",
+            "shl {tmp}, 1",
+            "shl {x}, 2",
+            "add {x}, {tmp}",
+            x = inout(reg) x,
+            tmp = out(reg) _,
+        );
+        std::arch::asm!(
+            "syscall",
+            in("rax") 1,
+            inlateout("rdi") a => b,
+            lateout("rcx") _,
+            options(nostack, preserves_flags),
+            clobber_abi("C", "system"),
+        );
+        asm!(
+            "call {f}",
+            f = sym foo::<u8>,
+            const N + 1,
+            #[cfg(x)]
+            in(reg) y,
+        );
+        asm!(
+            "jmp {}",
+            label {
+                return;
+            },
+        );
+    }
+}
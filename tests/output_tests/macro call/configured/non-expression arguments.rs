@@ -0,0 +1,16 @@
+// test-kind: before-after
+// macros: info = { kind = "format" }
+
+fn test() {
+    info!("a {}",   b);
+    info!(user = %name, "msg {}",   x);
+    info!(?x,   "msg");
+}
+
+// :after:
+
+fn test() {
+    info!("a {}", b);
+    info!(user = %name, "msg {}", x);
+    info!(?x, "msg");
+}
@@ -0,0 +1,33 @@
+// test-kind: before-after
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone,Copy)]
+    pub struct Flags: u32 {
+        const A = 0b0001;
+        /// B
+        const B = 0b0010;
+        const AB = Self::A.bits()|Self::B.bits();
+        const _ = !0;
+    }
+
+    impl Other : u8 {
+        const X = 1;
+    }
+}
+
+// :after:
+
+bitflags::bitflags! {
+    #[derive(Debug, Clone, Copy)]
+    pub struct Flags: u32 {
+        const A = 0b0001;
+        /// B
+        const B = 0b0010;
+        const AB = Self::A.bits() | Self::B.bits();
+        const _ = !0;
+    }
+
+    impl Other: u8 {
+        const X = 1;
+    }
+}
@@ -0,0 +1,37 @@
+// test-kind: before-after
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)]{
+        mod unix;   pub use unix::*;
+    } else if #[cfg( windows )] {
+        mod windows;
+        pub use  windows::*;
+    }
+    else {
+        fn  foo() {}
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(unix, not(target_os = "macos")))] { use a::b; }
+}
+
+// :after:
+
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        mod unix;
+        pub use unix::*;
+    } else if #[cfg(windows)] {
+        mod windows;
+        pub use windows::*;
+    } else {
+        fn foo() {}
+    }
+}
+
+cfg_if! {
+    if #[cfg(all(unix, not(target_os = "macos")))] {
+        use a::b;
+    }
+}
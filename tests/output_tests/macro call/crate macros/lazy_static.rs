@@ -0,0 +1,30 @@
+// test-kind: before-after
+
+lazy_static! {
+    /// Doc
+    #[allow(x)]
+    pub static ref   FOO : Mutex<Vec<u8>> = Mutex::new(Vec::new());
+    static ref BAR: HashMap<u32, &'static str> = { let mut m = HashMap::new(); m.insert(0, "foo"); m };
+}
+
+lazy_static::lazy_static! {
+    pub(crate) static ref REGEX: Regex = Regex::new("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+}
+
+// :after:
+
+lazy_static! {
+    /// Doc
+    #[allow(x)]
+    pub static ref FOO: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+    static ref BAR: HashMap<u32, &'static str> = {
+        let mut m = HashMap::new();
+        m.insert(0, "foo");
+        m
+    };
+}
+
+lazy_static::lazy_static! {
+    pub(crate) static ref REGEX: Regex =
+        Regex::new("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+}
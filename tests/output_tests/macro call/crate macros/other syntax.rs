@@ -0,0 +1,27 @@
+// test-kind: before-after
+
+bitflags! { weird   syntax here }
+
+lazy_static!(a   b);
+
+cfg_if! { if x {} }
+
+bitflags! {
+    struct A: u8 {
+        const B =  1;
+    }
+}
+
+// :after:
+
+bitflags! { weird   syntax here }
+
+lazy_static!(a   b);
+
+cfg_if! { if x {} }
+
+bitflags! {
+    struct A: u8 {
+        const B = 1;
+    }
+}
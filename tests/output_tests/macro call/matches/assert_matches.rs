@@ -0,0 +1,20 @@
+// test-kind: before-after
+
+fn test() {
+    assert_matches!(a,Some(_));
+    debug_assert_matches!(a,Some(x) if x > 1,"msg {}",b);
+    assert_matches!(aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa, Some(bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb), "message {}", cccccccccc);
+}
+
+// :after:
+
+fn test() {
+    assert_matches!(a, Some(_));
+    debug_assert_matches!(a, Some(x) if x > 1, "msg {}", b);
+    assert_matches!(
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa,
+        Some(bbbbbbbbbbbbbbbbbbbbbbbbbbbbbb),
+        "message {}",
+        cccccccccc,
+    );
+}
@@ -0,0 +1,21 @@
+// test-kind: before-after
+
+fn test() {
+    std::println!("{}",a);
+    core::panic!("x {}",a);
+    ::std::vec!(1,2);
+    alloc::format!("{}",a);
+    std::assert_eq!(a,b);
+    std::stringify!(a ,b);
+}
+
+// :after:
+
+fn test() {
+    std::println!("{}", a);
+    core::panic!("x {}", a);
+    ::std::vec![1, 2];
+    alloc::format!("{}", a);
+    std::assert_eq!(a, b);
+    std::stringify!(a ,b);
+}
@@ -0,0 +1,34 @@
+// test-kind: before-after
+
+fn test() {
+    my_dsl! {
+        foo => bar ,
+          baz => qux( 1,2 ),
+
+
+
+        nested => {
+          a ,b
+        },
+    }
+    my_dsl!(aaaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccccccccccc, ddddddddddddddddddd);
+}
+
+// :after:
+
+fn test() {
+    my_dsl! {
+        foo => bar,
+        baz => qux(1, 2),
+
+        nested => {
+            a, b
+        },
+    }
+    my_dsl!(
+        aaaaaaaaaaaaaaaaaaaaa,
+        bbbbbbbbbbbbbbbbbbbbbbbbbbbb,
+        cccccccccccccccccccccccccccc,
+        ddddddddddddddddddd
+    );
+}
@@ -0,0 +1,17 @@
+// test-kind: before-after
+
+fn test() {
+    my_dsl!( a ,b );
+    my_dsl!(x . y :: z , 1 .. 2 , a=>b, $ x);
+    let x = my_dsl![ a ; 3 ];
+    my_dsl!{a, (b ,c), [ d ]}
+}
+
+// :after:
+
+fn test() {
+    my_dsl!(a, b);
+    my_dsl!(x.y::z, 1 .. 2, a=>b, $ x);
+    let x = my_dsl![a; 3];
+    my_dsl! { a, (b, c), [d] }
+}
@@ -0,0 +1,7 @@
+// test-kind: no-change
+
+fn test() {
+    my_dsl!(a /* comment */ , b);
+    my_dsl!(1 . 0);
+    stringify!( a ,b );
+}
@@ -0,0 +1,13 @@
+// test-kind: before-after
+
+fn test() {
+    foo!(a b);
+    foo!(-  1);
+}
+
+// :after:
+
+fn test() {
+    foo!(a b);
+    foo!(-1);
+}
@@ -0,0 +1,7 @@
+// test-kind: no-change
+
+fn test() {
+    my_dsl!(x . 0 . 1);
+    foo!(|x| { x });
+    foo!(a, |x| { x });
+}
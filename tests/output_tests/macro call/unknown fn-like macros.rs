@@ -0,0 +1,27 @@
+// test-kind: before-after
+
+fn test() {
+    my_macro!( a ,b );
+    my_macro![1,2,3,];
+    my_macro!(aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccccc);
+    my_macro!(aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa, bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb, cccccccccccccccccccccc,);
+    my_macro!(struct X);
+}
+
+// :after:
+
+fn test() {
+    my_macro!(a, b);
+    my_macro![1, 2, 3,];
+    my_macro!(
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa,
+        bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb,
+        cccccccccccccccccccccc
+    );
+    my_macro!(
+        aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa,
+        bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb,
+        cccccccccccccccccccccc,
+    );
+    my_macro!(struct X);
+}
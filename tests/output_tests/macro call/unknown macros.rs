@@ -13,5 +13,7 @@ foo!();
 bar![
     // comment
 ];
-baz! {   ding
-    dong  }
+baz! {
+    ding
+    dong
+}
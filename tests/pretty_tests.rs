@@ -0,0 +1,92 @@
+#![feature(rustc_private)]
+
+extern crate rustc_ast;
+extern crate rustc_driver;
+extern crate rustc_span;
+extern crate thin_vec;
+
+use myrustfmt::config::Config;
+use myrustfmt::{format_ast_expr, format_token_stream};
+use rustc_ast::ast;
+use rustc_ast::token::{Delimiter, IdentIsRaw, TokenKind};
+use rustc_ast::tokenstream::{DelimSpacing, DelimSpan, Spacing, TokenStream, TokenTree};
+use rustc_span::DUMMY_SP;
+use rustc_span::edition::Edition;
+use rustc_span::source_map::Spanned;
+use rustc_span::symbol::{Ident, Symbol};
+use thin_vec::ThinVec;
+
+fn with_globals<T>(f: impl FnOnce() -> T) -> T {
+    rustc_span::create_session_globals_then(Edition::Edition2024, &[], None, f)
+}
+
+fn path_expr(name: &str) -> Box<ast::Expr> {
+    let path = ast::Path::from_ident(Ident::from_str(name));
+    expr(ast::ExprKind::Path(None, path))
+}
+
+fn expr(kind: ast::ExprKind) -> Box<ast::Expr> {
+    Box::new(ast::Expr {
+        id: ast::DUMMY_NODE_ID,
+        kind,
+        span: DUMMY_SP,
+        attrs: ThinVec::new(),
+        tokens: None,
+    })
+}
+
+fn ident(name: &str) -> TokenTree {
+    TokenTree::token_alone(
+        TokenKind::Ident(Symbol::intern(name), IdentIsRaw::No),
+        DUMMY_SP,
+    )
+}
+
+fn delimited(delim: Delimiter, tokens: Vec<TokenTree>) -> TokenTree {
+    TokenTree::Delimited(
+        DelimSpan::dummy(),
+        DelimSpacing::new(Spacing::Alone, Spacing::Alone),
+        delim,
+        TokenStream::new(tokens),
+    )
+}
+
+#[test]
+fn ast_expr() {
+    let formatted = with_globals(|| {
+        let args = ThinVec::from([path_expr("a"), path_expr("b")]);
+        let call = expr(ast::ExprKind::Call(path_expr("foo"), args));
+        let op = Spanned {
+            node: ast::BinOpKind::Add,
+            span: DUMMY_SP,
+        };
+        let expr = expr(ast::ExprKind::Binary(op, call, path_expr("c")));
+        format_ast_expr(&expr, Config::default(), 0)
+            .unwrap()
+            .expect_no_errors()
+    });
+    assert_eq!(formatted, "foo(a, b) + c");
+}
+
+#[test]
+fn token_stream() {
+    let formatted = with_globals(|| {
+        let tokens = TokenStream::new(vec![
+            ident("fn"),
+            ident("main"),
+            delimited(Delimiter::Parenthesis, vec![]),
+            delimited(
+                Delimiter::Brace,
+                vec![
+                    ident("foo"),
+                    delimited(Delimiter::Parenthesis, vec![]),
+                    TokenTree::token_alone(TokenKind::Semi, DUMMY_SP),
+                ],
+            ),
+        ]);
+        format_token_stream(&tokens, Config::default())
+            .unwrap()
+            .expect_no_errors()
+    });
+    assert_eq!(formatted, "fn main() {\n    foo();\n}\n");
+}
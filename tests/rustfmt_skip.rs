@@ -0,0 +1,25 @@
+#![feature(rustc_private)]
+
+use myrustfmt::config::Config;
+use myrustfmt::format_str;
+
+#[test]
+fn skip_module() {
+    let source = "// comment\n#![rustfmt::skip]\n\nfn  f( ) {}";
+    let formatted = format_str(source, Config::default())
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, source);
+}
+
+#[test]
+fn skip_macros_in_module() {
+    let source = "#![rustfmt::skip::macros(vec)]\n\nfn f( ) {\n    vec!( 1,2 );\n}\n";
+    let formatted = format_str(source, Config::default())
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(
+        formatted,
+        "#![rustfmt::skip::macros(vec)]\n\nfn f() {\n    vec!( 1,2 );\n}\n",
+    );
+}
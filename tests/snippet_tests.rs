@@ -0,0 +1,68 @@
+#![feature(rustc_private)]
+
+use myrustfmt::config::Config;
+use myrustfmt::{format_block_str, format_expr_str, format_item_str, format_pat_str, format_ty_str};
+
+#[test]
+fn expr_snippet() {
+    let formatted = format_expr_str("foo( a,b )", Config::default(), 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "foo(a, b)");
+}
+
+#[test]
+fn expr_snippet_with_indent() {
+    let formatted = format_expr_str(
+        "some_function(first_argument, second_argument)",
+        Config::default().max_width(40),
+        8,
+    )
+    .unwrap()
+    .expect_no_errors();
+    assert_eq!(
+        formatted,
+        "some_function(
+            first_argument,
+            second_argument,
+        )",
+    );
+}
+
+#[test]
+fn item_snippet() {
+    let formatted = format_item_str("fn  foo ( ) { x }", Config::default(), 4)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "fn foo() {\n        x\n    }");
+}
+
+#[test]
+fn block_snippet() {
+    let formatted = format_block_str("{ let x = 1; x }", Config::default(), 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "{\n    let x = 1;\n    x\n}");
+}
+
+#[test]
+fn pat_snippet() {
+    let formatted = format_pat_str("Some ( x )|None", Config::default(), 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "Some(x) | None");
+}
+
+#[test]
+fn ty_snippet() {
+    let formatted = format_ty_str("Vec < ( u8 ,u16 ) >", Config::default(), 0)
+        .unwrap()
+        .expect_no_errors();
+    assert_eq!(formatted, "Vec<(u8, u16)>");
+}
+
+#[test]
+fn snippet_parse_error() {
+    assert!(format_expr_str("a +", Config::default(), 0).is_err());
+    assert!(format_expr_str("a b", Config::default(), 0).is_err());
+}
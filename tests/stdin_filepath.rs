@@ -0,0 +1,130 @@
+mod util;
+
+use crate::util::SimpleOutput;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run_stdin(args: &[&str], input: &str) -> SimpleOutput {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_myrustfmt"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    SimpleOutput::expect(child.wait_with_output().unwrap())
+}
+
+#[test]
+fn errors_name_the_path() {
+    let output = run_stdin(
+        &["--max-width", "20", "--stdin-filepath", "src/not_a_file.rs"],
+        "fn f() {\n    aaaaaaaaaaaaaaaaaaaaaaaa;\n}\n",
+    );
+    assert_eq!(
+        output,
+        SimpleOutput {
+            stderr: "Max width exceeded at src/not_a_file.rs:2\n".to_string(),
+            stdout: "fn f() {\n    aaaaaaaaaaaaaaaaaaaaaaaa;\n}\n".to_string(),
+            code: 1,
+        },
+    );
+}
+
+#[test]
+fn with_dash() {
+    let output = run_stdin(
+        &["--stdin-filepath", "src/not_a_file.rs", "-"],
+        "fn f( ) {}\n",
+    );
+    assert_eq!(
+        output,
+        SimpleOutput {
+            stderr: String::new(),
+            stdout: "fn f() {}\n".to_string(),
+            code: 0,
+        },
+    );
+}
+
+#[test]
+fn ignored_path() {
+    let output = run_stdin(
+        &[
+            "--ignore",
+            "src/*.rs",
+            "--stdin-filepath",
+            "src/not_a_file.rs",
+        ],
+        "fn f( ) {}\n",
+    );
+    assert_eq!(
+        output,
+        SimpleOutput {
+            stderr: String::new(),
+            stdout: "fn f( ) {}\n".to_string(),
+            code: 0,
+        },
+    );
+}
+
+#[test]
+fn severity_option() {
+    let output = run_stdin(
+        &[
+            "--max-width",
+            "20",
+            "--max-width-exceeded",
+            "warn",
+            "--stdin-filepath",
+            "src/not_a_file.rs",
+        ],
+        "fn f() {\n    aaaaaaaaaaaaaaaaaaaaaaaa;\n}\n",
+    );
+    assert_eq!(
+        output,
+        SimpleOutput {
+            stderr: "Warning: Max width exceeded at src/not_a_file.rs:2\n".to_string(),
+            stdout: "fn f() {\n    aaaaaaaaaaaaaaaaaaaaaaaa;\n}\n".to_string(),
+            code: 0,
+        },
+    );
+}
+
+#[test]
+fn edition_from_manifest() {
+    let dir = std::env::temp_dir().join(format!("myrustfmt-stdin-edition-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"p\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    let path = dir.join("src/lib.rs");
+    let input = "fn f() {\n    panic!(\"{}\", x);\n}\n";
+    let inlined = "fn f() {\n    panic!(\"{x}\");\n}\n";
+    let path = path.to_str().unwrap();
+    let output = run_stdin(&["--inline-format-args", "--stdin-filepath", path], input);
+    assert_eq!(output.stdout, inlined);
+    assert_eq!(output.code, 0);
+    let output = run_stdin(
+        &[
+            "--inline-format-args",
+            "--edition",
+            "2018",
+            "--stdin-filepath",
+            path,
+        ],
+        input,
+    );
+    assert_eq!(output.stdout, input);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -8,15 +8,12 @@
     dyn_star,
     ergonomic_clones,
     fn_delegation,
-    gen_blocks,
     guard_patterns,
     never_patterns,
     pattern_types,
     pin_ergonomics,
-    postfix_match,
     return_type_notation,
     trait_alias,
-    try_blocks,
     type_ascription,
     unsafe_binders,
 )]
@@ -0,0 +1,102 @@
+mod util;
+
+use crate::util::SimpleOutput;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::Command;
+
+const UNFORMATTED: &str = "fn   main()  {}\n";
+const FORMATTED: &str = "fn main() {}\n";
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("myrustfmt-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn format(dir: &PathBuf, args: &[&str]) -> SimpleOutput {
+    let output = Command::new(env!("CARGO_BIN_EXE_myrustfmt"))
+        .current_dir(dir)
+        .arg("main.rs")
+        .args(args)
+        .output()
+        .unwrap();
+    SimpleOutput::expect(output)
+}
+
+#[test]
+fn preserves_permissions() {
+    let dir = temp_dir("preserves-permissions");
+    let path = dir.join("main.rs");
+    fs::write(&path, UNFORMATTED).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+    let output = format(&dir, &[]);
+    assert_eq!(output.code, 0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), FORMATTED);
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+    assert_eq!(Vec::from_iter(fs::read_dir(&dir).unwrap()).len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn backup() {
+    let dir = temp_dir("backup");
+    let path = dir.join("main.rs");
+    fs::write(&path, UNFORMATTED).unwrap();
+    let output = format(&dir, &["--backup"]);
+    assert_eq!(output.code, 0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), FORMATTED);
+    assert_eq!(
+        fs::read_to_string(dir.join("main.rs.bk")).unwrap(),
+        UNFORMATTED,
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn read_only_file() {
+    let dir = temp_dir("read-only-file");
+    let path = dir.join("main.rs");
+    fs::write(&path, UNFORMATTED).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o444)).unwrap();
+    let output = format(&dir, &[]);
+    assert_eq!(
+        output.stderr,
+        "Failed to write main.rs: the file is read-only\n",
+    );
+    assert_eq!(output.code, 1);
+    assert_eq!(fs::read_to_string(&path).unwrap(), UNFORMATTED);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn symlink() {
+    let dir = temp_dir("symlink");
+    let target = dir.join("target.rs");
+    fs::write(&target, UNFORMATTED).unwrap();
+    std::os::unix::fs::symlink("target.rs", dir.join("main.rs")).unwrap();
+    let output = format(&dir, &[]);
+    assert_eq!(output.code, 0);
+    assert!(
+        fs::symlink_metadata(dir.join("main.rs"))
+            .unwrap()
+            .is_symlink(),
+    );
+    assert_eq!(fs::read_to_string(&target).unwrap(), FORMATTED);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn hard_link() {
+    let dir = temp_dir("hard-link");
+    let other = dir.join("other.rs");
+    fs::write(&other, UNFORMATTED).unwrap();
+    fs::hard_link(&other, dir.join("main.rs")).unwrap();
+    let output = format(&dir, &[]);
+    assert_eq!(output.code, 0);
+    assert_eq!(fs::read_to_string(&other).unwrap(), FORMATTED);
+    fs::remove_dir_all(&dir).unwrap();
+}